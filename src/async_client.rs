@@ -0,0 +1,511 @@
+//! Async wrappers around the blocking [Client](crate::client::Client), for use from a tokio
+//! runtime. The underlying api client is blocking, so every call is offloaded to tokio's blocking
+//! thread pool. Callers no longer need to do this themselves.
+//!
+//! This module is only available with the `async` feature enabled.
+
+use crate::calls::{DecodeExtrinsic, Extrinsic};
+use crate::client::{ApiResult, BatchResult, Client, Included, NodeContractCreated, SharedClient};
+use crate::endpoints::Endpoints;
+use crate::events::{BalanceEvent, BlockEvent, SmartContractEvent, TFGridEvent, TfchainEvent};
+use crate::receipt::{Receipt, TxStatus};
+use crate::retry::RetryPolicy;
+use crate::types::{
+    AccountData, BlockNumber, Consumption, Contract, ContractResources, Farm, Hash, Node,
+    NodeCertification, NruConsumption, PublicConfig, Tft, Twin,
+};
+use codec::Encode;
+use futures::Stream;
+use runtime::Block;
+use sp_core::crypto::{AccountId32, Pair};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use substrate_api_client::sp_runtime::MultiSignature;
+use tokio::sync::mpsc;
+use tokio::task;
+
+/// Amount of items buffered by a [BlockingStream] before the producing thread blocks.
+const STREAM_BUFFER_SIZE: usize = 32;
+
+/// An async client exposing the same queries as the blocking [Client].
+pub struct AsyncClient<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    inner: SharedClient<P, E>,
+}
+
+impl<P, E> AsyncClient<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+    E: support::sp_runtime::traits::Member + support::Parameter,
+    TfchainEvent: From<E>,
+{
+    /// Create a new [AsyncClient]. Connecting to the chain is blocking, so this is done on the
+    /// blocking thread pool as well.
+    pub async fn new(url: String, signer: Option<P>) -> ApiResult<AsyncClient<P, E>> {
+        Self::with_retry_policy(url, signer, RetryPolicy::default()).await
+    }
+
+    /// Create a new [AsyncClient], which retries failed calls according to the given
+    /// [RetryPolicy].
+    pub async fn with_retry_policy(
+        url: String,
+        signer: Option<P>,
+        retry_policy: RetryPolicy,
+    ) -> ApiResult<AsyncClient<P, E>> {
+        Self::with_endpoints(Endpoints::from(url), signer, retry_policy).await
+    }
+
+    /// Create a new [AsyncClient], which fails over between the given [Endpoints].
+    pub async fn with_endpoints(
+        endpoints: Endpoints,
+        signer: Option<P>,
+        retry_policy: RetryPolicy,
+    ) -> ApiResult<AsyncClient<P, E>> {
+        let client =
+            task::spawn_blocking(move || Client::with_endpoints(endpoints, signer, retry_policy))
+                .await
+                .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))?;
+        Ok(AsyncClient {
+            inner: SharedClient::new(client),
+        })
+    }
+
+    /// Get a handle to the blocking client backing this [AsyncClient].
+    pub fn blocking_client(&self) -> SharedClient<P, E> {
+        self.inner.clone()
+    }
+
+    pub async fn submit_and_watch<C>(&self, call: C, until: TxStatus) -> ApiResult<Receipt>
+    where
+        C: Encode + Clone + Send + 'static,
+    {
+        self.blocking(move |c| c.submit_and_watch(call, until))
+            .await
+    }
+
+    pub async fn create_twin(&self, ip: &str) -> ApiResult<Receipt> {
+        let ip = ip.to_string();
+        self.blocking(move |c| c.create_twin(&ip)).await
+    }
+
+    pub async fn get_twin_by_id(&self, id: u32, block: Option<Hash>) -> ApiResult<Option<Twin>> {
+        self.blocking(move |c| c.get_twin_by_id(id, block)).await
+    }
+
+    pub async fn update_twin(&self, ip: &str) -> ApiResult<Included<TFGridEvent>> {
+        let ip = ip.to_string();
+        self.blocking(move |c| c.update_twin(&ip)).await
+    }
+
+    pub async fn delete_twin(&self, twin_id: u32) -> ApiResult<Included<TFGridEvent>> {
+        self.blocking(move |c| c.delete_twin(twin_id)).await
+    }
+
+    pub async fn add_twin_entity(
+        &self,
+        twin_id: u32,
+        entity_id: u32,
+        signature: &[u8],
+    ) -> ApiResult<Included<TFGridEvent>> {
+        let signature = signature.to_vec();
+        self.blocking(move |c| c.add_twin_entity(twin_id, entity_id, &signature))
+            .await
+    }
+
+    pub async fn delete_twin_entity(
+        &self,
+        twin_id: u32,
+        entity_id: u32,
+    ) -> ApiResult<Included<TFGridEvent>> {
+        self.blocking(move |c| c.delete_twin_entity(twin_id, entity_id))
+            .await
+    }
+
+    pub async fn create_farm(&self, name: &str) -> ApiResult<Receipt> {
+        let name = name.to_string();
+        self.blocking(move |c| c.create_farm(&name)).await
+    }
+
+    pub async fn update_farm(
+        &self,
+        farm_id: u32,
+        name: &str,
+        pricing_policy_id: u32,
+    ) -> ApiResult<Included<Farm>> {
+        let name = name.to_string();
+        self.blocking(move |c| c.update_farm(farm_id, &name, pricing_policy_id))
+            .await
+    }
+
+    pub async fn add_farm_ip(
+        &self,
+        farm_id: u32,
+        ip: &str,
+        gateway: &str,
+    ) -> ApiResult<Included<Farm>> {
+        let (ip, gateway) = (ip.to_string(), gateway.to_string());
+        self.blocking(move |c| c.add_farm_ip(farm_id, &ip, &gateway))
+            .await
+    }
+
+    pub async fn remove_farm_ip(&self, farm_id: u32, ip: &str) -> ApiResult<Included<Farm>> {
+        let ip = ip.to_string();
+        self.blocking(move |c| c.remove_farm_ip(farm_id, &ip)).await
+    }
+
+    pub async fn add_stellar_payout_v2address(
+        &self,
+        farm_id: u32,
+        address: &str,
+    ) -> ApiResult<Included<Farm>> {
+        let address = address.to_string();
+        self.blocking(move |c| c.add_stellar_payout_v2address(farm_id, &address))
+            .await
+    }
+
+    pub async fn set_farm_dedicated(
+        &self,
+        farm_id: u32,
+        dedicated: bool,
+    ) -> ApiResult<Included<Farm>> {
+        self.blocking(move |c| c.set_farm_dedicated(farm_id, dedicated))
+            .await
+    }
+
+    pub async fn delete_farm(&self, farm_id: u32) -> ApiResult<Included<u32>> {
+        self.blocking(move |c| c.delete_farm(farm_id)).await
+    }
+
+    pub async fn get_farm_by_id(&self, id: u32, block: Option<Hash>) -> ApiResult<Option<Farm>> {
+        self.blocking(move |c| c.get_farm_by_id(id, block)).await
+    }
+
+    pub async fn get_farm_id_by_name(&self, name: &str) -> ApiResult<u32> {
+        let name = name.to_string();
+        self.blocking(move |c| c.get_farm_id_by_name(&name)).await
+    }
+
+    pub async fn farm_count(&self, block: Option<Hash>) -> ApiResult<u32> {
+        self.blocking(move |c| c.farm_count(block)).await
+    }
+
+    pub async fn get_account_free_balance(
+        &self,
+        account: &AccountId32,
+        block: Option<Hash>,
+    ) -> ApiResult<AccountData> {
+        let account = account.clone();
+        self.blocking(move |c| c.get_account_free_balance(&account, block))
+            .await
+    }
+
+    pub async fn create_node(&self, node: &Node) -> ApiResult<Included<Node>> {
+        let node = node.clone();
+        self.blocking(move |c| c.create_node(&node)).await
+    }
+
+    pub async fn update_node(&self, node: &Node) -> ApiResult<Included<Node>> {
+        let node = node.clone();
+        self.blocking(move |c| c.update_node(&node)).await
+    }
+
+    pub async fn delete_node(&self, node_id: u32) -> ApiResult<Included<u32>> {
+        self.blocking(move |c| c.delete_node(node_id)).await
+    }
+
+    pub async fn add_node_public_config(
+        &self,
+        farm_id: u32,
+        node_id: u32,
+        public_config: &PublicConfig,
+    ) -> ApiResult<Included<PublicConfig>> {
+        let public_config = public_config.clone();
+        self.blocking(move |c| c.add_node_public_config(farm_id, node_id, &public_config))
+            .await
+    }
+
+    pub async fn report_uptime(&self, uptime: u64) -> ApiResult<Included<TFGridEvent>> {
+        self.blocking(move |c| c.report_uptime(uptime)).await
+    }
+
+    pub async fn set_node_certification(
+        &self,
+        node_id: u32,
+        certification: NodeCertification,
+    ) -> ApiResult<Included<NodeCertification>> {
+        self.blocking(move |c| c.set_node_certification(node_id, certification))
+            .await
+    }
+
+    pub async fn transfer(
+        &self,
+        to: &AccountId32,
+        amount: Tft,
+    ) -> ApiResult<Included<BalanceEvent>> {
+        let to = to.clone();
+        self.blocking(move |c| c.transfer(&to, amount)).await
+    }
+
+    pub async fn transfer_keep_alive(
+        &self,
+        to: &AccountId32,
+        amount: Tft,
+    ) -> ApiResult<Included<BalanceEvent>> {
+        let to = to.clone();
+        self.blocking(move |c| c.transfer_keep_alive(&to, amount))
+            .await
+    }
+
+    pub async fn get_node_by_id(
+        &self,
+        node_id: u32,
+        block: Option<Hash>,
+    ) -> ApiResult<Option<Node>> {
+        self.blocking(move |c| c.get_node_by_id(node_id, block))
+            .await
+    }
+
+    pub async fn node_count(&self, block: Option<Hash>) -> ApiResult<u32> {
+        self.blocking(move |c| c.node_count(block)).await
+    }
+
+    pub async fn create_node_contract(
+        &self,
+        node_id: u32,
+        deployment_data: &[u8],
+        deployment_hash: &[u8],
+        public_ips: u32,
+    ) -> ApiResult<Included<NodeContractCreated>> {
+        let (data, hash) = (deployment_data.to_vec(), deployment_hash.to_vec());
+        self.blocking(move |c| c.create_node_contract(node_id, &data, &hash, public_ips))
+            .await
+    }
+
+    pub async fn update_node_contract(
+        &self,
+        contract_id: u64,
+        deployment_data: &[u8],
+        deployment_hash: &[u8],
+    ) -> ApiResult<Included<Contract>> {
+        let (data, hash) = (deployment_data.to_vec(), deployment_hash.to_vec());
+        self.blocking(move |c| c.update_node_contract(contract_id, &data, &hash))
+            .await
+    }
+
+    pub async fn create_name_contract(&self, name: &str) -> ApiResult<Included<Contract>> {
+        let name = name.to_string();
+        self.blocking(move |c| c.create_name_contract(&name)).await
+    }
+
+    pub async fn create_rent_contract(&self, node_id: u32) -> ApiResult<Included<Contract>> {
+        self.blocking(move |c| c.create_rent_contract(node_id))
+            .await
+    }
+
+    pub async fn cancel_contract(
+        &self,
+        contract_id: u64,
+    ) -> ApiResult<Included<SmartContractEvent>> {
+        self.blocking(move |c| c.cancel_contract(contract_id)).await
+    }
+
+    pub async fn add_reports(&self, reports: &[Consumption]) -> BatchResult<Consumption> {
+        let reports = reports.to_vec();
+        self.blocking(move |c| c.add_reports(&reports)).await
+    }
+
+    pub async fn add_nru_reports(&self, reports: &[NruConsumption]) -> BatchResult<NruConsumption> {
+        let reports = reports.to_vec();
+        self.blocking(move |c| c.add_nru_reports(&reports)).await
+    }
+
+    pub async fn get_contract_by_id(
+        &self,
+        contract_id: u64,
+        block: Option<Hash>,
+    ) -> ApiResult<Option<Contract>> {
+        self.blocking(move |c| c.get_contract_by_id(contract_id, block))
+            .await
+    }
+
+    pub async fn get_contract_resources(
+        &self,
+        contract_id: u64,
+        block: Option<Hash>,
+    ) -> ApiResult<Option<ContractResources>> {
+        self.blocking(move |c| c.get_contract_resources(contract_id, block))
+            .await
+    }
+
+    pub async fn contract_count(&self, block: Option<Hash>) -> ApiResult<u64> {
+        self.blocking(move |c| c.contract_count(block)).await
+    }
+
+    pub async fn get_farm_payout_address(
+        &self,
+        farm_id: u32,
+        block: Option<Hash>,
+    ) -> ApiResult<Option<String>> {
+        self.blocking(move |c| c.get_farm_payout_address(farm_id, block))
+            .await
+    }
+
+    pub async fn get_block_by_hash(&self, block_hash: &str) -> ApiResult<Option<Block>> {
+        let block_hash = block_hash.to_string();
+        self.blocking(move |c| c.get_block_by_hash(&block_hash))
+            .await
+    }
+
+    pub async fn get_block_events(&self, block: Option<Hash>) -> ApiResult<Vec<BlockEvent>> {
+        self.blocking(move |c| c.get_block_events(block)).await
+    }
+
+    pub async fn block_timestamp(&self, block: Option<Hash>) -> ApiResult<i64> {
+        self.blocking(move |c| c.block_timestamp(block)).await
+    }
+
+    pub async fn get_hash_at_height(&self, height: BlockNumber) -> ApiResult<Option<Hash>> {
+        self.blocking(move |c| c.get_hash_at_height(height)).await
+    }
+
+    pub async fn finalized_height(&self) -> ApiResult<BlockNumber> {
+        self.blocking(|c| c.finalized_height()).await
+    }
+
+    pub async fn height_at_timestamp(&self, ts: i64) -> ApiResult<BlockNumber> {
+        self.blocking(move |c| c.height_at_timestamp(ts)).await
+    }
+
+    /// Get a [Stream] of finalized block headers. Like the blocking
+    /// [FinalizedHeadSubscription](crate::client::FinalizedHeadSubscription), this stream will
+    /// never finish naturally. If it does it indicates a receiving error, and a new stream should
+    /// be created.
+    pub async fn finalized_block_headers(&self) -> ApiResult<FinalizedHeadStream> {
+        let subscription = self.blocking(|c| c.finalized_block_headers()).await?;
+        Ok(FinalizedHeadStream {
+            inner: BlockingStream::new(subscription),
+        })
+    }
+
+    /// Get a [Stream] of the height and hash of every finalized block, starting at `from` if
+    /// set. Unlike [AsyncClient::finalized_block_headers], this stream recovers from broken
+    /// subscriptions and never skips a block, see
+    /// [FinalizedBlocks](crate::client::FinalizedBlocks).
+    pub async fn finalized_blocks(&self, from: Option<BlockNumber>) -> FinalizedBlockStream {
+        FinalizedBlockStream {
+            inner: BlockingStream::new(self.inner.finalized_blocks(from)),
+        }
+    }
+
+    /// Run a blocking call on the client on the blocking thread pool.
+    async fn blocking<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&Client<P, E>) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let client = self.inner.clone();
+        task::spawn_blocking(move || f(&client))
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+}
+
+impl<P, E> AsyncClient<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+    E: support::sp_runtime::traits::Member + support::Parameter + DecodeExtrinsic,
+    TfchainEvent: From<E>,
+{
+    pub async fn get_block_extrinsics(&self, block: Option<Hash>) -> ApiResult<Vec<Extrinsic>> {
+        self.blocking(move |c| c.get_block_extrinsics(block)).await
+    }
+}
+
+impl<P, E> Clone for AsyncClient<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<P, E> From<SharedClient<P, E>> for AsyncClient<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    fn from(inner: SharedClient<P, E>) -> Self {
+        AsyncClient { inner }
+    }
+}
+
+/// A [Stream] of finalized block headers.
+pub struct FinalizedHeadStream {
+    inner: BlockingStream<runtime::Header>,
+}
+
+impl Stream for FinalizedHeadStream {
+    type Item = runtime::Header;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+/// A [Stream] of the height and hash of every finalized block, see
+/// [AsyncClient::finalized_blocks].
+pub struct FinalizedBlockStream {
+    inner: BlockingStream<ApiResult<(BlockNumber, Hash)>>,
+}
+
+impl Stream for FinalizedBlockStream {
+    type Item = ApiResult<(BlockNumber, Hash)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+/// A [Stream] fed by a blocking [Iterator], which is driven on the blocking thread pool. The
+/// iterator is stopped once the stream is dropped.
+pub(crate) struct BlockingStream<T> {
+    rx: mpsc::Receiver<T>,
+}
+
+impl<T> BlockingStream<T>
+where
+    T: Send + 'static,
+{
+    pub(crate) fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_SIZE);
+        task::spawn_blocking(move || {
+            for item in iter {
+                // Receiver is gone, so nobody is interested in the remaining items.
+                if tx.blocking_send(item).is_err() {
+                    return;
+                }
+            }
+        });
+        BlockingStream { rx }
+    }
+}
+
+impl<T> Stream for BlockingStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
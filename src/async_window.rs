@@ -0,0 +1,224 @@
+//! Async counterpart of the [window](crate::window) module.
+//!
+//! This module is only available with the `async` feature enabled.
+
+use crate::async_client::BlockingStream;
+use crate::calls;
+use crate::events;
+use crate::filter::EventFilter;
+use crate::scanner::RangeScanner;
+use crate::types::{BlockNumber, Contract, Farm, Node, Resources};
+use crate::window::{BlockStream, EventTypedClient, FinalizedBlock, Window, WindowResult};
+use chrono::prelude::*;
+use futures::Stream;
+use sp_core::crypto::Pair;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use substrate_api_client::sp_runtime::MultiSignature;
+use tokio::task;
+
+/// An async view into the blockchain storage at a certain point in time. See [Window] for
+/// details.
+pub struct AsyncWindow<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    inner: Arc<Window<P>>,
+}
+
+impl<P> AsyncWindow<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    /// Create a new [AsyncWindow] at the given height. If the used block height does not exist
+    /// yet on the chain, Ok(None) is returned.
+    pub async fn at_height<C>(
+        client: C,
+        height: BlockNumber,
+    ) -> WindowResult<Option<AsyncWindow<P>>>
+    where
+        C: Into<EventTypedClient<P>> + Send + 'static,
+    {
+        blocking(move || Window::at_height(client, height))
+            .await
+            .map(|w| w.map(AsyncWindow::from))
+    }
+
+    /// Indicates if the [AsyncWindow] points to data in the past, or the current head. If this is
+    /// false, all storage values returned are considered volatile.
+    pub fn is_historic(&self) -> bool {
+        self.inner.is_historic()
+    }
+
+    /// Get the [AsyncWindow] for the next block.
+    pub async fn advance(&self) -> WindowResult<Option<AsyncWindow<P>>> {
+        self.map_window(|w| w.advance()).await
+    }
+
+    /// Get the [AsyncWindow] pointing to the block `amount` blocks past the one pointed to by the
+    /// current [AsyncWindow].
+    pub async fn advance_by(&self, amount: BlockNumber) -> WindowResult<Option<AsyncWindow<P>>> {
+        self.map_window(move |w| w.advance_by(amount)).await
+    }
+
+    /// Get the [AsyncWindow] for the previous block.
+    pub async fn previous(&self) -> WindowResult<Option<AsyncWindow<P>>> {
+        self.map_window(|w| w.previous()).await
+    }
+
+    /// Get the [AsyncWindow] pointing to the block `amount` blocks before the one pointed to by
+    /// the current [AsyncWindow].
+    pub async fn previous_by(&self, amount: BlockNumber) -> WindowResult<Option<AsyncWindow<P>>> {
+        self.map_window(move |w| w.previous_by(amount)).await
+    }
+
+    /// Get the [events](events::BlockEvent) for the block pointed at by the window.
+    pub async fn events(&self) -> WindowResult<Vec<events::BlockEvent>> {
+        self.with_window(|w| w.events()).await
+    }
+
+    /// Get the [events](events::BlockEvent) for the block pointed at by the window which match a
+    /// [filter](EventFilter).
+    pub async fn filtered_events(
+        &self,
+        filter: &EventFilter,
+    ) -> WindowResult<Vec<events::BlockEvent>> {
+        let filter = filter.clone();
+        self.with_window(move |w| w.filtered_events(&filter)).await
+    }
+
+    /// Get the [extrinsics](calls::Extrinsic) in the block pointed at by the window, together with
+    /// the events they emitted.
+    pub async fn extrinsics(&self) -> WindowResult<Vec<calls::Extrinsic>> {
+        self.with_window(|w| w.extrinsics()).await
+    }
+
+    /// Gets the date at which the block pointed to by this [AsyncWindow] was made.
+    pub async fn date(&self) -> WindowResult<DateTime<Utc>> {
+        self.with_window(|w| w.date()).await
+    }
+
+    /// Get the height of the block pointed at by the current [AsyncWindow].
+    pub fn height(&self) -> WindowResult<BlockNumber> {
+        self.inner.height()
+    }
+
+    /// Get a [Stream] returning all farms in the current [AsyncWindow].
+    pub async fn farms(&self) -> WindowResult<WindowStream<Farm>> {
+        let iter = self.with_window(|w| w.farms()).await?;
+        Ok(WindowStream::new(iter))
+    }
+
+    /// Get a [Stream] returning all nodes in the current [AsyncWindow].
+    pub async fn nodes(&self) -> WindowResult<WindowStream<Node>> {
+        let iter = self.with_window(|w| w.nodes()).await?;
+        Ok(WindowStream::new(iter))
+    }
+
+    /// Get a [Stream] returning all contracts in the current [AsyncWindow]. If live is true, only
+    /// contracts currently deployed will be returned.
+    pub async fn contracts(&self, live: bool) -> WindowResult<WindowStream<(Contract, Resources)>> {
+        let iter = self.with_window(move |w| w.contracts(live)).await?;
+        Ok(WindowStream::new(iter))
+    }
+
+    /// Get the farm stellar address in the block pointed at by the current [AsyncWindow].
+    pub async fn farm_payout_address(&self, farm_id: u32) -> WindowResult<Option<String>> {
+        self.with_window(move |w| w.farm_payout_address(farm_id))
+            .await
+    }
+
+    /// Get a handle to the blocking [Window] backing this [AsyncWindow].
+    pub fn blocking_window(&self) -> Arc<Window<P>> {
+        self.inner.clone()
+    }
+
+    async fn with_window<T, F>(&self, f: F) -> WindowResult<T>
+    where
+        F: FnOnce(&Window<P>) -> WindowResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let window = self.inner.clone();
+        blocking(move || f(&window)).await
+    }
+
+    async fn map_window<F>(&self, f: F) -> WindowResult<Option<AsyncWindow<P>>>
+    where
+        F: FnOnce(&Window<P>) -> WindowResult<Option<Window<P>>> + Send + 'static,
+    {
+        self.with_window(f).await.map(|w| w.map(AsyncWindow::from))
+    }
+}
+
+impl<P> From<Window<P>> for AsyncWindow<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    fn from(w: Window<P>) -> Self {
+        AsyncWindow { inner: Arc::new(w) }
+    }
+}
+
+/// Get a [Stream] of every finalized block, with its timestamp and events. If `from` is set, the
+/// stream starts at the block with that height. See [BlockStream] for details.
+pub fn finalized_blocks<C, P>(client: C, from: Option<BlockNumber>) -> WindowStream<FinalizedBlock>
+where
+    C: Into<EventTypedClient<P>>,
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    WindowStream::new(BlockStream::new(client, from))
+}
+
+/// Get a [Stream] of the blocks scanned by a [RangeScanner], in order of their height. The
+/// progress of the scan is the height of the last block returned successfully.
+pub fn scan_range<P>(scanner: RangeScanner<P>) -> WindowStream<FinalizedBlock>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    WindowStream::new(scanner.scan())
+}
+
+/// A [Stream] over the values in storage pointed at by an [AsyncWindow], or over the blocks of a
+/// [BlockStream] or a [RangeScanner].
+pub struct WindowStream<T> {
+    inner: BlockingStream<WindowResult<T>>,
+}
+
+impl<T> WindowStream<T>
+where
+    T: Send + 'static,
+{
+    fn new<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = WindowResult<T>> + Send + 'static,
+    {
+        WindowStream {
+            inner: BlockingStream::new(iter),
+        }
+    }
+}
+
+impl<T> Stream for WindowStream<T> {
+    type Item = WindowResult<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+/// Run a blocking closure on the blocking thread pool.
+async fn blocking<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+}
@@ -0,0 +1,165 @@
+//! Decoding of the extrinsics in a block into calls.
+//!
+//! Similar to the [events](crate::events) module, the calls of the pallets are retyped here, so
+//! they are no longer generic over the [Runtime](runtime::Runtime). Calls which are not retyped
+//! are still identified by the name of their pallet and call, see [TfchainCall::Other].
+
+mod balance;
+mod dao;
+mod smart_contract;
+mod tfgrid;
+mod tft_bridge;
+
+pub use balance::Call as BalanceCall;
+pub use dao::Call as DaoCall;
+pub use smart_contract::Call as SmartContractCall;
+pub use tfgrid::Call as TFGridCall;
+pub use tft_bridge::Call as TftBridgeCall;
+
+use crate::events::{SystemEvent, TfchainEvent};
+use crate::receipt::Outcome;
+use crate::types::Tft;
+use codec::{Compact, Decode, Encode};
+use sp_core::crypto::AccountId32;
+use sp_runtime::generic::Era;
+use sp_runtime::MultiAddress;
+
+/// An extrinsic in a block, together with the events emitted while applying it.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extrinsic {
+    /// The index of the extrinsic in the block.
+    pub index: u32,
+    /// The signer of the extrinsic. Inherents, like the timestamp of the block, are not signed.
+    pub signer: Option<Signer>,
+    /// The call dispatched by the extrinsic.
+    pub call: TfchainCall,
+    /// The events emitted while applying the extrinsic, in the order in which they were emitted.
+    pub events: Vec<TfchainEvent>,
+}
+
+impl Extrinsic {
+    /// Get the result of applying the extrinsic, from the `ExtrinsicSuccess` or `ExtrinsicFailed`
+    /// event it emitted. Returns None if neither event is present.
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.events.iter().find_map(|event| match event {
+            TfchainEvent::System(SystemEvent::ExtrinsicSuccess(_)) => Some(Outcome::Success),
+            TfchainEvent::System(SystemEvent::ExtrinsicFailed(error, _)) => {
+                Some(Outcome::Failed(error.clone()))
+            }
+            _ => None,
+        })
+    }
+}
+
+/// The signer of an extrinsic, with the nonce and tip from its signed extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Signer {
+    /// The account which signed the extrinsic.
+    pub account: AccountId32,
+    /// The nonce of the account used for the extrinsic.
+    pub nonce: u32,
+    /// The tip paid to the block author, on top of the fees.
+    pub tip: Tft,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TfchainCall {
+    TFGrid(Box<TFGridCall>),
+    SmartContract(SmartContractCall),
+    Balance(BalanceCall),
+    TftBridge(TftBridgeCall),
+    Dao(DaoCall),
+    /// A call which the current library does not decode. The names of the pallet and call are
+    /// only resolved if the call is retrieved through a [Client](crate::client::Client), which is
+    /// connected to the same runtime version as the one which produced the block.
+    Other {
+        pallet_index: u8,
+        call_index: u8,
+        pallet: Option<String>,
+        call: Option<String>,
+    },
+}
+
+impl TfchainCall {
+    /// Create a [TfchainCall::Other] from an encoded call, which starts with the index of the
+    /// pallet followed by the index of the call in that pallet.
+    pub(crate) fn other(encoded: &[u8]) -> TfchainCall {
+        TfchainCall::Other {
+            pallet_index: encoded.first().copied().unwrap_or_default(),
+            call_index: encoded.get(1).copied().unwrap_or_default(),
+            pallet: None,
+            call: None,
+        }
+    }
+}
+
+impl From<runtime::Call> for TfchainCall {
+    fn from(c: runtime::Call) -> Self {
+        let other = TfchainCall::other(&c.encode());
+        let call = match c {
+            runtime::Call::TfgridModule(c) => TFGridCall::try_from(c)
+                .map(|c| TfchainCall::TFGrid(Box::new(c)))
+                .ok(),
+            runtime::Call::SmartContractModule(c) => SmartContractCall::try_from(c)
+                .map(TfchainCall::SmartContract)
+                .ok(),
+            runtime::Call::Balances(c) => BalanceCall::try_from(c).map(TfchainCall::Balance).ok(),
+            runtime::Call::TFTBridgeModule(c) => {
+                TftBridgeCall::try_from(c).map(TfchainCall::TftBridge).ok()
+            }
+            runtime::Call::Dao(c) => DaoCall::try_from(c).map(TfchainCall::Dao).ok(),
+            _ => None,
+        };
+        call.unwrap_or(other)
+    }
+}
+
+/// Decoding of extrinsics with the types of a runtime. This is implemented for the event types of
+/// the runtimes known to this library, so a client which decodes events with a runtime also
+/// decodes extrinsics with that runtime.
+pub trait DecodeExtrinsic {
+    /// Decode an encoded extrinsic into its signer, if it is signed, and its call.
+    fn decode_extrinsic(encoded: &[u8]) -> Result<(Option<Signer>, TfchainCall), codec::Error>;
+}
+
+impl DecodeExtrinsic for runtime::Event {
+    fn decode_extrinsic(encoded: &[u8]) -> Result<(Option<Signer>, TfchainCall), codec::Error> {
+        let xt = runtime::UncheckedExtrinsic::decode(&mut &encoded[..])?;
+        let signer = xt
+            .signature
+            .map(|(address, _, extra)| signer(address, &extra))
+            .transpose()?;
+        Ok((signer, xt.function.into()))
+    }
+}
+
+/// Get the [Signer] of an extrinsic from its address and signed extensions.
+pub(crate) fn signer<X>(
+    address: MultiAddress<AccountId32, ()>,
+    extra: &X,
+) -> Result<Signer, codec::Error>
+where
+    X: Encode,
+{
+    let account = lookup(address).ok_or("extrinsic is not signed by an account id")?;
+    // The signed extensions don't expose their fields, but only the era, nonce and tip are part of
+    // their encoding. The other extensions are only included in the signed payload.
+    let (_, nonce, tip) = <(Era, Compact<u32>, Compact<u128>)>::decode(&mut &extra.encode()[..])?;
+    Ok(Signer {
+        account,
+        nonce: nonce.0,
+        tip: tip.0.into(),
+    })
+}
+
+/// Get the account id from an address in a call. The runtime only accepts account ids as
+/// addresses, so other kinds of addresses can't be part of an extrinsic in a block.
+pub(crate) fn lookup(address: MultiAddress<AccountId32, ()>) -> Option<AccountId32> {
+    match address {
+        MultiAddress::Id(account) => Some(account),
+        _ => None,
+    }
+}
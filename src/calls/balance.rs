@@ -0,0 +1,40 @@
+use super::lookup;
+use crate::types::Tft;
+use sp_core::crypto::AccountId32;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Call {
+    /// Transfer some free balance to another account. \[dest, value\]
+    Transfer(AccountId32, Tft),
+    /// Set the balances of an account, as root. \[who, free, reserved\]
+    SetBalance(AccountId32, Tft, Tft),
+    /// Transfer some free balance between accounts, as root. \[source, dest, value\]
+    ForceTransfer(AccountId32, AccountId32, Tft),
+    /// Transfer some free balance to another account, without killing the sender.
+    /// \[dest, value\]
+    TransferKeepAlive(AccountId32, Tft),
+}
+
+impl TryFrom<pallet_balance::Call<runtime::Runtime>> for Call {
+    type Error = pallet_balance::Call<runtime::Runtime>;
+
+    fn try_from(bc: pallet_balance::Call<runtime::Runtime>) -> Result<Self, Self::Error> {
+        let call = match bc.clone() {
+            pallet_balance::Call::transfer(dest, value) => {
+                lookup(dest).map(|dest| Call::Transfer(dest, value.into()))
+            }
+            pallet_balance::Call::set_balance(who, free, reserved) => {
+                lookup(who).map(|who| Call::SetBalance(who, free.into(), reserved.into()))
+            }
+            pallet_balance::Call::force_transfer(source, dest, value) => lookup(source)
+                .zip(lookup(dest))
+                .map(|(source, dest)| Call::ForceTransfer(source, dest, value.into())),
+            pallet_balance::Call::transfer_keep_alive(dest, value) => {
+                lookup(dest).map(|dest| Call::TransferKeepAlive(dest, value.into()))
+            }
+            _ => None,
+        };
+        call.ok_or(bc)
+    }
+}
@@ -0,0 +1,69 @@
+use super::TfchainCall;
+use crate::types::Hash;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Call {
+    /// Propose a call to be executed once enough farmers vote for it.
+    Propose {
+        threshold: u32,
+        action: Box<TfchainCall>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        description: Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        link: Vec<u8>,
+    },
+    /// Vote on a proposal with the weight of a farm of the signer.
+    Vote {
+        farm_id: u32,
+        proposal_hash: Hash,
+        approve: bool,
+    },
+    /// Veto a proposal, as a council member. \[proposal hash\]
+    Veto(Hash),
+    /// Close a proposal after its voting period ended.
+    Close {
+        proposal_hash: Hash,
+        proposal_index: u32,
+    },
+}
+
+impl TryFrom<pallet_dao::Call<runtime::Runtime>> for Call {
+    type Error = pallet_dao::Call<runtime::Runtime>;
+
+    fn try_from(pdc: pallet_dao::Call<runtime::Runtime>) -> Result<Self, Self::Error> {
+        Ok(match pdc {
+            pallet_dao::Call::<runtime::Runtime>::propose {
+                threshold,
+                action,
+                description,
+                link,
+            } => Call::Propose {
+                threshold,
+                action: Box::new((*action).into()),
+                description,
+                link,
+            },
+            pallet_dao::Call::<runtime::Runtime>::vote {
+                farm_id,
+                proposal_hash,
+                approve,
+            } => Call::Vote {
+                farm_id,
+                proposal_hash,
+                approve,
+            },
+            pallet_dao::Call::<runtime::Runtime>::veto { proposal_hash } => {
+                Call::Veto(proposal_hash)
+            }
+            pallet_dao::Call::<runtime::Runtime>::close {
+                proposal_hash,
+                proposal_index,
+            } => Call::Close {
+                proposal_hash,
+                proposal_index,
+            },
+            pdc => return Err(pdc),
+        })
+    }
+}
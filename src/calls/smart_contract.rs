@@ -0,0 +1,67 @@
+use crate::types::{Consumption, NruConsumption};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Call {
+    /// \[node id, deployment data, deployment hash, amount of public ips\]
+    CreateNodeContract(
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        u32,
+    ),
+    /// \[contract id, deployment data, deployment hash\]
+    UpdateNodeContract(
+        u64,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// \[contract id\]
+    CancelContract(u64),
+    /// Consumption reports of the node of the signer. \[reports\]
+    AddReports(Vec<Consumption>),
+    /// \[name\]
+    CreateNameContract(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// Network usage reports of the node of the signer. \[reports\]
+    AddNruReports(Vec<NruConsumption>),
+    /// \[node id\]
+    CreateRentContract(u32),
+}
+
+impl TryFrom<pallet_smart_contract::Call<runtime::Runtime>> for Call {
+    type Error = pallet_smart_contract::Call<runtime::Runtime>;
+
+    fn try_from(scc: pallet_smart_contract::Call<runtime::Runtime>) -> Result<Self, Self::Error> {
+        Ok(match scc {
+            pallet_smart_contract::Call::create_node_contract(
+                node_id,
+                deployment_data,
+                deployment_hash,
+                public_ips,
+            ) => Call::CreateNodeContract(node_id, deployment_data, deployment_hash, public_ips),
+            pallet_smart_contract::Call::update_node_contract(
+                contract_id,
+                deployment_data,
+                deployment_hash,
+            ) => Call::UpdateNodeContract(contract_id, deployment_data, deployment_hash),
+            pallet_smart_contract::Call::cancel_contract(contract_id) => {
+                Call::CancelContract(contract_id)
+            }
+            pallet_smart_contract::Call::add_reports(reports) => {
+                Call::AddReports(reports.into_iter().map(Consumption::from).collect())
+            }
+            pallet_smart_contract::Call::create_name_contract(name) => {
+                Call::CreateNameContract(name)
+            }
+            pallet_smart_contract::Call::add_nru_reports(reports) => {
+                Call::AddNruReports(reports.into_iter().map(NruConsumption::from).collect())
+            }
+            pallet_smart_contract::Call::create_rent_contract(node_id) => {
+                Call::CreateRentContract(node_id)
+            }
+            scc => return Err(scc),
+        })
+    }
+}
@@ -0,0 +1,186 @@
+use crate::types::{
+    FarmCertification, Interface, Location, NodeCertification, PublicConfig, Resources,
+};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Call {
+    /// Create a twin for the signer. \[ip\]
+    CreateTwin(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// Update the ip of the twin of the signer. \[ip\]
+    UpdateTwin(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// \[twin id\]
+    DeleteTwin(u32),
+    /// Link an entity to a twin. \[twin id, entity id, signature\]
+    AddTwinEntity(
+        u32,
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_hex"))] Vec<u8>,
+    ),
+    /// \[twin id, entity id\]
+    DeleteTwinEntity(u32, u32),
+
+    /// \[name\]
+    CreateFarm(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// \[farm id, name, pricing policy id\]
+    UpdateFarm(
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        u32,
+    ),
+    /// \[farm id, stellar address\]
+    AddStellarPayoutV2Address(
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// \[farm id, certification\]
+    SetFarmCertification(u32, FarmCertification),
+    /// \[farm id, dedicated\]
+    SetFarmDedicated(u32, bool),
+    /// \[farm id, ip, gateway\]
+    AddFarmIp(
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// \[farm id, ip\]
+    RemoveFarmIp(
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+    /// \[farm id\]
+    DeleteFarm(u32),
+
+    CreateNode {
+        farm_id: u32,
+        resources: Resources,
+        location: Location,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        country: Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        city: Vec<u8>,
+        interfaces: Vec<Interface>,
+        secure_boot: bool,
+        virtualized: bool,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        serial_number: Vec<u8>,
+    },
+    UpdateNode {
+        node_id: u32,
+        farm_id: u32,
+        resources: Resources,
+        location: Location,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        country: Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        city: Vec<u8>,
+        interfaces: Vec<Interface>,
+        secure_boot: bool,
+        virtualized: bool,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
+        serial_number: Vec<u8>,
+    },
+    /// \[node id, certification\]
+    SetNodeCertification(u32, NodeCertification),
+    /// Report the uptime of the node of the signer. \[uptime\]
+    ReportUptime(u64),
+    /// \[farm id, node id, public config\]
+    AddNodePublicConfig(u32, u32, PublicConfig),
+    /// Remove a node from its farm. \[node id\]
+    DeleteNodeFarm(u32),
+}
+
+impl TryFrom<pallet_tfgrid::Call<runtime::Runtime>> for Call {
+    type Error = pallet_tfgrid::Call<runtime::Runtime>;
+
+    fn try_from(tfgc: pallet_tfgrid::Call<runtime::Runtime>) -> Result<Self, Self::Error> {
+        Ok(match tfgc {
+            pallet_tfgrid::Call::create_twin(ip) => Call::CreateTwin(ip),
+            pallet_tfgrid::Call::update_twin(ip) => Call::UpdateTwin(ip),
+            pallet_tfgrid::Call::delete_twin(twin_id) => Call::DeleteTwin(twin_id),
+            pallet_tfgrid::Call::add_twin_entity(twin_id, entity_id, signature) => {
+                Call::AddTwinEntity(twin_id, entity_id, signature)
+            }
+            pallet_tfgrid::Call::delete_twin_entity(twin_id, entity_id) => {
+                Call::DeleteTwinEntity(twin_id, entity_id)
+            }
+            pallet_tfgrid::Call::create_farm(name) => Call::CreateFarm(name),
+            pallet_tfgrid::Call::update_farm(farm_id, name, pricing_policy_id) => {
+                Call::UpdateFarm(farm_id, name, pricing_policy_id)
+            }
+            pallet_tfgrid::Call::add_stellar_payout_v2address(farm_id, address) => {
+                Call::AddStellarPayoutV2Address(farm_id, address)
+            }
+            pallet_tfgrid::Call::set_farm_certification(farm_id, certification) => {
+                Call::SetFarmCertification(farm_id, certification.into())
+            }
+            pallet_tfgrid::Call::set_farm_dedicated(farm_id, dedicated) => {
+                Call::SetFarmDedicated(farm_id, dedicated)
+            }
+            pallet_tfgrid::Call::add_farm_ip(farm_id, ip, gateway) => {
+                Call::AddFarmIp(farm_id, ip, gateway)
+            }
+            pallet_tfgrid::Call::remove_farm_ip(farm_id, ip) => Call::RemoveFarmIp(farm_id, ip),
+            pallet_tfgrid::Call::delete_farm(farm_id) => Call::DeleteFarm(farm_id),
+            pallet_tfgrid::Call::create_node(
+                farm_id,
+                resources,
+                location,
+                country,
+                city,
+                interfaces,
+                secure_boot,
+                virtualized,
+                serial_number,
+            ) => Call::CreateNode {
+                farm_id,
+                resources: resources.into(),
+                location: location.into(),
+                country,
+                city,
+                interfaces: interfaces.into_iter().map(Interface::from).collect(),
+                secure_boot,
+                virtualized,
+                serial_number,
+            },
+            pallet_tfgrid::Call::update_node(
+                node_id,
+                farm_id,
+                resources,
+                location,
+                country,
+                city,
+                interfaces,
+                secure_boot,
+                virtualized,
+                serial_number,
+            ) => Call::UpdateNode {
+                node_id,
+                farm_id,
+                resources: resources.into(),
+                location: location.into(),
+                country,
+                city,
+                interfaces: interfaces.into_iter().map(Interface::from).collect(),
+                secure_boot,
+                virtualized,
+                serial_number,
+            },
+            pallet_tfgrid::Call::set_node_certification(node_id, certification) => {
+                Call::SetNodeCertification(node_id, certification.into())
+            }
+            pallet_tfgrid::Call::report_uptime(uptime) => Call::ReportUptime(uptime),
+            pallet_tfgrid::Call::add_node_public_config(farm_id, node_id, config) => {
+                Call::AddNodePublicConfig(farm_id, node_id, config.into())
+            }
+            pallet_tfgrid::Call::delete_node_farm(node_id) => Call::DeleteNodeFarm(node_id),
+            tfgc => return Err(tfgc),
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use crate::types::{AccountId32, Tft};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Call {
+    /// \[validator\]
+    AddBridgeValidator(AccountId32),
+    /// \[validator\]
+    RemoveBridgeValidator(AccountId32),
+    /// \[fee account\]
+    SetFeeAccount(AccountId32),
+    /// \[fee\]
+    SetWithdrawFee(Tft),
+    /// \[fee\]
+    SetDepositFee(Tft),
+    /// Swap TFT of the signer to a stellar address. \[stellar address, amount\]
+    SwapToStellar(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+    ),
+    /// \[stellar transaction, target, amount\]
+    ProposeOrVoteMintTransaction(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        AccountId32,
+        Tft,
+    ),
+    /// \[burn id, stellar address, amount, signature, stellar public key, sequence number\]
+    ProposeBurnTransactionOrAddSig(
+        u64,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        u64,
+    ),
+    /// \[burn id\]
+    SetBurnTransactionExecuted(u64),
+    /// \[stellar transaction hash, stellar address, amount, signature, stellar public key,
+    /// sequence number\]
+    CreateRefundTransactionOrAddSig(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        u64,
+    ),
+    /// \[stellar transaction hash\]
+    SetRefundTransactionExecuted(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+}
+
+impl TryFrom<pallet_tft_bridge::Call<runtime::Runtime>> for Call {
+    type Error = pallet_tft_bridge::Call<runtime::Runtime>;
+
+    fn try_from(tbc: pallet_tft_bridge::Call<runtime::Runtime>) -> Result<Self, Self::Error> {
+        Ok(match tbc {
+            pallet_tft_bridge::Call::add_bridge_validator(target) => {
+                Call::AddBridgeValidator(target)
+            }
+            pallet_tft_bridge::Call::remove_bridge_validator(target) => {
+                Call::RemoveBridgeValidator(target)
+            }
+            pallet_tft_bridge::Call::set_fee_account(target) => Call::SetFeeAccount(target),
+            pallet_tft_bridge::Call::set_withdraw_fee(amount) => {
+                Call::SetWithdrawFee(amount.into())
+            }
+            pallet_tft_bridge::Call::set_deposit_fee(amount) => Call::SetDepositFee(amount.into()),
+            pallet_tft_bridge::Call::swap_to_stellar(target, amount) => {
+                Call::SwapToStellar(target, amount.into())
+            }
+            pallet_tft_bridge::Call::propose_or_vote_mint_transaction(tx_id, target, amount) => {
+                Call::ProposeOrVoteMintTransaction(tx_id, target, amount.into())
+            }
+            pallet_tft_bridge::Call::propose_burn_transaction_or_add_sig(
+                tx_id,
+                target,
+                amount,
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ) => Call::ProposeBurnTransactionOrAddSig(
+                tx_id,
+                target,
+                amount.into(),
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ),
+            pallet_tft_bridge::Call::set_burn_transaction_executed(tx_id) => {
+                Call::SetBurnTransactionExecuted(tx_id)
+            }
+            pallet_tft_bridge::Call::create_refund_transaction_or_add_sig(
+                tx_hash,
+                target,
+                amount,
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ) => Call::CreateRefundTransactionOrAddSig(
+                tx_hash,
+                target,
+                amount.into(),
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ),
+            pallet_tft_bridge::Call::set_refund_transaction_executed(tx_hash) => {
+                Call::SetRefundTransactionExecuted(tx_hash)
+            }
+            tbc => return Err(tbc),
+        })
+    }
+}
@@ -1,16 +1,39 @@
 //! Low level client to interact with the chain. For upstream usage, other than constructing a
 //! [Client], you likely want to look at the [window](crate::window) module.
 
-use crate::events::TfchainEvent;
+use crate::calls::{DaoCall, DecodeExtrinsic, Extrinsic, TfchainCall};
+use crate::endpoints::{EndpointStatus, Endpoints, Served};
+use crate::error::Error;
+use crate::events::{
+    BalanceEvent, BlockEvent, Phase, SmartContractEvent, SystemEvent, TFGridEvent, TfchainEvent,
+};
+use crate::nonce::NonceManager;
+use crate::receipt::{Outcome, Receipt, TxStatus};
+use crate::retry::{ErrorKind, RetryPolicy};
 use crate::types::ContractResources;
 pub use crate::types::Hash;
-use crate::types::{AccountData, AccountInfo, BlockNumber, Contract, Farm, Node, Twin};
+use crate::types::{
+    AccountData, AccountInfo, BlockNumber, Consumption, Contract, ContractData, DispatchError,
+    Farm, Node, NodeCertification, NruConsumption, PublicConfig, PublicIP, Tft, Twin,
+};
+use crate::validation;
+use codec::{Compact, Decode, Encode};
 use runtime::Block;
+use serde_json::json;
 pub use sp_core::crypto::AccountId32;
+pub use sp_core::storage::StorageKey;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use substrate_api_client::sp_runtime::generic::{self, Era};
+use substrate_api_client::sp_runtime::OpaqueExtrinsic;
 use substrate_api_client::{
-    compose_extrinsic, Api, ApiClientError, UncheckedExtrinsicV4, XtStatus,
+    compose_call, compose_extrinsic_offline, Api, ApiClientError, GenericAddress,
+    UncheckedExtrinsicV4, XtStatus,
 };
 
 pub use sp_core::crypto::Pair;
@@ -18,7 +41,42 @@ pub use substrate_api_client::sp_runtime::MultiSignature;
 
 const BLOCK_TIME_SECONDS: i64 = 6;
 
-pub type ApiResult<T> = Result<T, ApiClientError>;
+/// The maximum amount of reports submitted in a single extrinsic. Larger batches are split.
+pub const MAX_REPORTS_PER_EXTRINSIC: usize = 100;
+
+/// The maximum amount of storage keys requested at once when paging through a storage map, and
+/// the maximum amount of values fetched at once. Nodes refuse to return more keys in a single call.
+pub const STORAGE_PAGE_SIZE: u32 = 1000;
+
+pub type ApiResult<T> = Result<T, Error>;
+
+/// A block with the extrinsics in their encoded form, which can be decoded regardless of the
+/// runtime version which produced it.
+type OpaqueBlock = generic::Block<runtime::Header, OpaqueExtrinsic>;
+
+/// The state of the periodic health checks of the endpoints of a [Client].
+struct HealthCheck<P> {
+    /// When the last health check was started.
+    last: Instant,
+    /// The result of the health check running in the background, if any.
+    running: Option<mpsc::Receiver<Probes<P>>>,
+}
+
+impl<P> HealthCheck<P> {
+    fn since(last: Instant) -> Self {
+        HealthCheck {
+            last,
+            running: None,
+        }
+    }
+}
+
+/// The connections made to the endpoints during a health check, together with their finalized
+/// height. The endpoint which was active when the check started is not probed.
+struct Probes<P> {
+    active: usize,
+    results: Vec<(Option<Api<P>>, Option<BlockNumber>)>,
+}
 
 pub struct SharedClient<P, E>
 where
@@ -45,17 +103,44 @@ where
         TfchainEvent: From<U>,
     {
         // TODO: Improve this
+        let (raw, _) = self.inner.current();
         SharedClient {
             inner: Arc::new(Client {
-                inner: RawClient {
-                    api: self.inner.inner.api.clone(),
+                inner: RwLock::new(Arc::new(RawClient {
+                    api: raw.api.clone(),
                     _marker: std::marker::PhantomData,
-                },
+                })),
+                endpoints: self.inner.endpoints.clone(),
+                active: AtomicUsize::new(self.inner.active.load(Ordering::SeqCst)),
+                health: Mutex::new(HealthCheck::since(self.inner.health.lock().unwrap().last)),
+                signer: self.inner.signer.clone(),
+                retry_policy: self.inner.retry_policy.clone(),
+                spec_versions: self.inner.spec_versions.clone(),
             }),
         }
     }
 }
 
+impl<P, E> SharedClient<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+    E: support::sp_runtime::traits::Member + support::Parameter,
+    TfchainEvent: From<E>,
+{
+    /// Follow the finalized blocks of the chain, see [FinalizedBlocks]. If `from` is set, blocks
+    /// are returned starting at that height, so a caller can resume after the last block it
+    /// handled. Otherwise they start at the first finalized head received.
+    pub fn finalized_blocks(&self, from: Option<BlockNumber>) -> FinalizedBlocks<P, E> {
+        FinalizedBlocks {
+            client: self.clone(),
+            subscription: None,
+            next: from,
+            head: 0,
+        }
+    }
+}
+
 impl<P, E> Clone for SharedClient<P, E>
 where
     P: Pair,
@@ -84,7 +169,30 @@ where
     P: Pair,
     MultiSignature: From<P::Signature>,
 {
-    inner: RawClient<P, E>,
+    /// The connection currently in use. Calls run on a clone of the [Arc], so switching to
+    /// another endpoint does not wait for calls which are in progress.
+    inner: RwLock<Arc<RawClient<P, E>>>,
+    endpoints: Endpoints,
+    /// Index of the endpoint `inner` is connected to. Only modified while holding the write lock
+    /// on `inner`.
+    active: AtomicUsize,
+    health: Mutex<HealthCheck<P>>,
+    signer: Option<P>,
+    retry_policy: RetryPolicy,
+    spec_versions: Arc<Mutex<SpecVersionCache>>,
+}
+
+impl<P, E> Client<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    /// The connection currently in use, together with the index of its endpoint. The lock is
+    /// only held while cloning, so calls never block a switch to another endpoint.
+    fn current(&self) -> (Arc<RawClient<P, E>>, usize) {
+        let raw = self.inner.read().unwrap();
+        (raw.clone(), self.active.load(Ordering::SeqCst))
+    }
 }
 
 impl<P, E> Client<P, E>
@@ -94,134 +202,390 @@ where
     E: support::sp_runtime::traits::Member + support::Parameter,
     TfchainEvent: From<E>,
 {
-    pub fn new(url: String, signer: Option<P>) -> Client<P, E> {
-        let mut api = Api::new(url).unwrap();
-        if let Some(signer) = signer {
-            api = api.set_signer(signer);
-        }
-        Client {
-            inner: RawClient {
-                api,
-                _marker: std::marker::PhantomData,
-            },
-        }
+    pub fn new(url: String, signer: Option<P>) -> ApiResult<Client<P, E>> {
+        Self::with_retry_policy(url, signer, RetryPolicy::default())
+    }
+
+    /// Create a new [Client], which retries failed calls according to the given [RetryPolicy].
+    pub fn with_retry_policy(
+        url: String,
+        signer: Option<P>,
+        retry_policy: RetryPolicy,
+    ) -> ApiResult<Client<P, E>> {
+        Self::with_endpoints(Endpoints::from(url), signer, retry_policy)
     }
 
-    pub fn create_twin(&self, ip: &str) -> ApiResult<Option<Hash>> {
-        let mut res = self.inner.create_twin(ip);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
+    /// Create a new [Client], which fails over between the given [Endpoints]. The first endpoint
+    /// which can be reached is used initially.
+    pub fn with_endpoints(
+        endpoints: Endpoints,
+        signer: Option<P>,
+        retry_policy: RetryPolicy,
+    ) -> ApiResult<Client<P, E>> {
+        let mut last_err = None;
+        for (idx, url) in endpoints.urls().iter().enumerate() {
+            match connect(url, signer.clone()) {
+                Ok(api) => {
+                    return Ok(Client {
+                        inner: RwLock::new(Arc::new(RawClient {
+                            api,
+                            _marker: std::marker::PhantomData,
+                        })),
+                        endpoints,
+                        active: AtomicUsize::new(idx),
+                        health: Mutex::new(HealthCheck::since(Instant::now())),
+                        signer,
+                        retry_policy,
+                        spec_versions: Arc::new(Mutex::new(SpecVersionCache::default())),
+                    })
+                }
+                Err(e) => last_err = Some(e),
             }
-            res = self.inner.create_twin(ip);
         }
 
-        res
+        // Endpoints always contains at least 1 url, so we can only get here after an error.
+        Err(last_err.unwrap_or_else(|| Error::InvalidInput("no endpoints configured".into())))
     }
 
-    pub fn get_twin_by_id(&self, id: u32) -> ApiResult<Twin> {
-        let mut res = self.inner.get_twin_by_id(id);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_twin_by_id(id);
-        }
+    /// The [RetryPolicy] used by this client.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
 
-        res
+    /// The [Endpoints] this client can connect to.
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    /// The url of the endpoint currently used by this client.
+    pub fn active_endpoint(&self) -> &str {
+        &self.endpoints.urls()[self.active.load(Ordering::SeqCst)]
+    }
+
+    /// Check the finalized height of all configured endpoints. If the active endpoint can't be
+    /// reached, or lags too far behind, the client switches to the best endpoint.
+    pub fn check_endpoints(&self) -> Vec<EndpointStatus> {
+        self.health.lock().unwrap().last = Instant::now();
+        let active = self.active.load(Ordering::SeqCst);
+        let probes = probe_endpoints(self.endpoints.urls(), active, self.signer.clone());
+        self.apply_probes(probes)
     }
 
-    pub fn create_farm(&self, name: &str) -> ApiResult<Option<Hash>> {
-        let mut res = self.inner.create_farm(name);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
+    /// Switch to the best endpoint according to the result of a health check, and report the
+    /// status of all endpoints. If the client failed over while the endpoints were probed, the
+    /// result is outdated and the client keeps the endpoint it is using.
+    fn apply_probes(&self, probes: Probes<P>) -> Vec<EndpointStatus> {
+        let (mut apis, mut heights): (Vec<_>, Vec<_>) = probes.results.into_iter().unzip();
+        if probes.active == self.active.load(Ordering::SeqCst) {
+            heights[probes.active] = self.current().0.finalized_height().ok();
+            if let Some(selected) = self.endpoints.select(&heights, probes.active) {
+                if let Some(api) = apis[selected].take() {
+                    self.switch_to(selected, api);
+                }
             }
-            res = self.inner.create_farm(name);
         }
 
-        res
+        let active = self.active.load(Ordering::SeqCst);
+        self.endpoints
+            .urls()
+            .iter()
+            .zip(heights)
+            .enumerate()
+            .map(|(idx, (url, finalized_height))| EndpointStatus {
+                url: url.clone(),
+                finalized_height,
+                active: idx == active,
+            })
+            .collect()
     }
 
-    pub fn get_farm_by_id(&self, id: u32, block: Option<Hash>) -> ApiResult<Option<Farm>> {
-        let mut res = self.inner.get_farm_by_id(id, block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_farm_by_id(id, block);
+    /// Execute a call on the [RawClient], with the retry and failover behavior of this client, and
+    /// report which endpoint served it.
+    pub fn served<T, F>(&self, f: F) -> ApiResult<Served<T>>
+    where
+        F: Fn(&RawClient<P, E>) -> ApiResult<T>,
+    {
+        self.maybe_check_endpoints();
+
+        let mut attempts = self.retry_policy.start();
+        loop {
+            let (raw, endpoint) = self.current();
+            let err = match f(&raw) {
+                Ok(value) => {
+                    return Ok(Served {
+                        value,
+                        endpoint: self.endpoints.urls()[endpoint].clone(),
+                    })
+                }
+                Err(err) => err,
+            };
+            let delay = match attempts.next_delay(ErrorKind::of(&err)) {
+                Some(delay) => delay,
+                None => return Err(err),
+            };
+            thread::sleep(delay);
+            self.failover(endpoint);
         }
+    }
 
-        res
+    /// Get the height of the latest finalized block.
+    pub fn finalized_height(&self) -> ApiResult<BlockNumber> {
+        self.retry(|c| c.finalized_height())
     }
 
-    pub fn get_farm_id_by_name(&self, name: &str) -> ApiResult<u32> {
-        let mut res = self.inner.get_farm_id_by_name(name);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_farm_id_by_name(name);
+    /// Get the spec version of the runtime which produced the block with the given height and
+    /// hash. Resolved spec versions are cached, so scanning a range of blocks only requires a
+    /// request around runtime upgrades.
+    pub fn spec_version_at(&self, height: BlockNumber, hash: Hash) -> ApiResult<u32> {
+        if let Some(spec_version) = self.spec_versions.lock().unwrap().get(height) {
+            return Ok(spec_version);
         }
+        let spec_version = self.retry(|c| c.spec_version(Some(hash)))?;
+        self.spec_versions
+            .lock()
+            .unwrap()
+            .insert(height, spec_version);
+        Ok(spec_version)
+    }
 
-        res
+    /// Submit a call and watch it until it reaches the given status. See
+    /// [RawClient::submit_and_watch]. Only errors which occur before the extrinsic is sent are
+    /// retried, so an extrinsic is never submitted twice.
+    pub fn submit_and_watch<C>(&self, call: C, until: TxStatus) -> ApiResult<Receipt>
+    where
+        C: Encode + Clone,
+    {
+        self.retry(|c| c.submit_and_watch(call.clone(), until))
+    }
+
+    pub fn create_twin(&self, ip: &str) -> ApiResult<Receipt> {
+        self.retry(|c| c.create_twin(ip))
+    }
+
+    pub fn get_twin_by_id(&self, id: u32, block: Option<Hash>) -> ApiResult<Option<Twin>> {
+        self.retry(|c| c.get_twin_by_id(id, block))
+    }
+
+    pub fn update_twin(&self, ip: &str) -> ApiResult<Included<TFGridEvent>> {
+        self.retry(|c| c.update_twin(ip))
+    }
+
+    pub fn delete_twin(&self, twin_id: u32) -> ApiResult<Included<TFGridEvent>> {
+        self.retry(|c| c.delete_twin(twin_id))
+    }
+
+    pub fn add_twin_entity(
+        &self,
+        twin_id: u32,
+        entity_id: u32,
+        signature: &[u8],
+    ) -> ApiResult<Included<TFGridEvent>> {
+        self.retry(|c| c.add_twin_entity(twin_id, entity_id, signature))
+    }
+
+    pub fn delete_twin_entity(
+        &self,
+        twin_id: u32,
+        entity_id: u32,
+    ) -> ApiResult<Included<TFGridEvent>> {
+        self.retry(|c| c.delete_twin_entity(twin_id, entity_id))
+    }
+
+    pub fn create_farm(&self, name: &str) -> ApiResult<Receipt> {
+        self.retry(|c| c.create_farm(name))
+    }
+
+    pub fn update_farm(
+        &self,
+        farm_id: u32,
+        name: &str,
+        pricing_policy_id: u32,
+    ) -> ApiResult<Included<Farm>> {
+        self.retry(|c| c.update_farm(farm_id, name, pricing_policy_id))
+    }
+
+    pub fn add_farm_ip(&self, farm_id: u32, ip: &str, gateway: &str) -> ApiResult<Included<Farm>> {
+        self.retry(|c| c.add_farm_ip(farm_id, ip, gateway))
+    }
+
+    pub fn remove_farm_ip(&self, farm_id: u32, ip: &str) -> ApiResult<Included<Farm>> {
+        self.retry(|c| c.remove_farm_ip(farm_id, ip))
+    }
+
+    pub fn add_stellar_payout_v2address(
+        &self,
+        farm_id: u32,
+        address: &str,
+    ) -> ApiResult<Included<Farm>> {
+        self.submit_then_read(
+            |c| c.register_stellar_payout_v2address(farm_id, address),
+            |c, block| {
+                c.get_farm_by_id(farm_id, Some(block))?
+                    .ok_or_else(|| Error::NotFound(format!("farm {}", farm_id)))
+            },
+        )
+    }
+
+    pub fn set_farm_dedicated(&self, farm_id: u32, dedicated: bool) -> ApiResult<Included<Farm>> {
+        self.retry(|c| c.set_farm_dedicated(farm_id, dedicated))
+    }
+
+    pub fn delete_farm(&self, farm_id: u32) -> ApiResult<Included<u32>> {
+        self.retry(|c| c.delete_farm(farm_id))
+    }
+
+    pub fn get_farm_by_id(&self, id: u32, block: Option<Hash>) -> ApiResult<Option<Farm>> {
+        self.retry(|c| c.get_farm_by_id(id, block))
+    }
+
+    pub fn get_farm_id_by_name(&self, name: &str) -> ApiResult<u32> {
+        self.retry(|c| c.get_farm_id_by_name(name))
     }
 
     pub fn farm_count(&self, block: Option<Hash>) -> ApiResult<u32> {
-        let mut res = self.inner.farm_count(block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.farm_count(block);
-        }
+        self.retry(|c| c.farm_count(block))
+    }
 
-        res
+    /// Get the storage keys of all farms, see [RawClient::get_farm_keys].
+    pub fn get_farm_keys(&self, block: Option<Hash>) -> ApiResult<Vec<StorageKey>> {
+        self.retry(|c| c.get_farm_keys(block))
     }
 
-    pub fn get_account_free_balance(&self, account: &AccountId32) -> ApiResult<AccountData> {
-        let mut res = self.inner.get_account_free_balance(account);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_account_free_balance(account);
-        }
+    /// Get the farms stored under the given keys, see [RawClient::get_farms_at].
+    pub fn get_farms_at(&self, keys: &[StorageKey], block: Option<Hash>) -> ApiResult<Vec<Farm>> {
+        self.retry(|c| c.get_farms_at(keys, block))
+    }
 
-        res
+    pub fn get_account_free_balance(
+        &self,
+        account: &AccountId32,
+        block: Option<Hash>,
+    ) -> ApiResult<AccountData> {
+        self.retry(|c| c.get_account_free_balance(account, block))
     }
 
-    pub fn get_node_by_id(&self, node_id: u32, block: Option<Hash>) -> ApiResult<Option<Node>> {
-        let mut res = self.inner.get_node_by_id(node_id, block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_node_by_id(node_id, block);
-        }
+    pub fn create_node(&self, node: &Node) -> ApiResult<Included<Node>> {
+        self.retry(|c| c.create_node(node))
+    }
 
-        res
+    pub fn update_node(&self, node: &Node) -> ApiResult<Included<Node>> {
+        self.retry(|c| c.update_node(node))
+    }
+
+    pub fn delete_node(&self, node_id: u32) -> ApiResult<Included<u32>> {
+        self.retry(|c| c.delete_node(node_id))
+    }
+
+    pub fn add_node_public_config(
+        &self,
+        farm_id: u32,
+        node_id: u32,
+        public_config: &PublicConfig,
+    ) -> ApiResult<Included<PublicConfig>> {
+        self.retry(|c| c.add_node_public_config(farm_id, node_id, public_config))
+    }
+
+    pub fn report_uptime(&self, uptime: u64) -> ApiResult<Included<TFGridEvent>> {
+        self.retry(|c| c.report_uptime(uptime))
+    }
+
+    pub fn set_node_certification(
+        &self,
+        node_id: u32,
+        certification: NodeCertification,
+    ) -> ApiResult<Included<NodeCertification>> {
+        self.retry(|c| c.set_node_certification(node_id, certification.clone()))
+    }
+
+    /// Transfer an amount to another account, see [RawClient::transfer]. Transfers move value,
+    /// so they are never retried. If an error is returned, check the balance of the signer before
+    /// transferring again.
+    pub fn transfer(&self, to: &AccountId32, amount: Tft) -> ApiResult<Included<BalanceEvent>> {
+        self.once(|c| c.transfer(to, amount))
+    }
+
+    /// Transfer an amount to another account, see [RawClient::transfer_keep_alive]. Transfers
+    /// move value, so they are never retried. If an error is returned, check the balance of the
+    /// signer before transferring again.
+    pub fn transfer_keep_alive(
+        &self,
+        to: &AccountId32,
+        amount: Tft,
+    ) -> ApiResult<Included<BalanceEvent>> {
+        self.once(|c| c.transfer_keep_alive(to, amount))
+    }
+
+    pub fn get_node_by_id(&self, node_id: u32, block: Option<Hash>) -> ApiResult<Option<Node>> {
+        self.retry(|c| c.get_node_by_id(node_id, block))
     }
 
     pub fn node_count(&self, block: Option<Hash>) -> ApiResult<u32> {
-        let mut res = self.inner.node_count(block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.node_count(block);
-        }
+        self.retry(|c| c.node_count(block))
+    }
 
-        res
+    /// Get the storage keys of all nodes, see [RawClient::get_node_keys].
+    pub fn get_node_keys(&self, block: Option<Hash>) -> ApiResult<Vec<StorageKey>> {
+        self.retry(|c| c.get_node_keys(block))
+    }
+
+    /// Get the nodes stored under the given keys, see [RawClient::get_nodes_at].
+    pub fn get_nodes_at(&self, keys: &[StorageKey], block: Option<Hash>) -> ApiResult<Vec<Node>> {
+        self.retry(|c| c.get_nodes_at(keys, block))
+    }
+
+    pub fn create_node_contract(
+        &self,
+        node_id: u32,
+        deployment_data: &[u8],
+        deployment_hash: &[u8],
+        public_ips: u32,
+    ) -> ApiResult<Included<NodeContractCreated>> {
+        self.retry(|c| {
+            c.create_node_contract(node_id, deployment_data, deployment_hash, public_ips)
+        })
+    }
+
+    pub fn update_node_contract(
+        &self,
+        contract_id: u64,
+        deployment_data: &[u8],
+        deployment_hash: &[u8],
+    ) -> ApiResult<Included<Contract>> {
+        self.retry(|c| c.update_node_contract(contract_id, deployment_data, deployment_hash))
+    }
+
+    pub fn create_name_contract(&self, name: &str) -> ApiResult<Included<Contract>> {
+        self.retry(|c| c.create_name_contract(name))
+    }
+
+    pub fn create_rent_contract(&self, node_id: u32) -> ApiResult<Included<Contract>> {
+        self.retry(|c| c.create_rent_contract(node_id))
+    }
+
+    pub fn cancel_contract(&self, contract_id: u64) -> ApiResult<Included<SmartContractEvent>> {
+        self.retry(|c| c.cancel_contract(contract_id))
+    }
+
+    /// Submit consumption reports of the node of the signer. See [RawClient::add_reports]. Every
+    /// batch is retried separately, so batches which were already included are not submitted
+    /// again.
+    pub fn add_reports(&self, reports: &[Consumption]) -> BatchResult<Consumption> {
+        submit_batches(
+            reports,
+            |r| (r.contract_id, r.timestamp),
+            |batch| self.retry(|c| c.add_reports_batch(batch)),
+        )
+    }
+
+    /// Submit NRU consumption reports of the node of the signer. See
+    /// [RawClient::add_nru_reports]. Every batch is retried separately, so batches which were
+    /// already included are not submitted again.
+    pub fn add_nru_reports(&self, reports: &[NruConsumption]) -> BatchResult<NruConsumption> {
+        submit_batches(
+            reports,
+            |r| (r.contract_id, r.timestamp),
+            |batch| self.retry(|c| c.add_nru_reports_batch(batch)),
+        )
     }
 
     pub fn get_contract_by_id(
@@ -229,16 +593,7 @@ where
         contract_id: u64,
         block: Option<Hash>,
     ) -> ApiResult<Option<Contract>> {
-        let mut res = self.inner.get_contract_by_id(contract_id, block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_contract_by_id(contract_id, block);
-        }
-
-        res
+        self.retry(|c| c.get_contract_by_id(contract_id, block))
     }
 
     pub fn get_contract_resources(
@@ -246,29 +601,25 @@ where
         contract_id: u64,
         block: Option<Hash>,
     ) -> ApiResult<Option<ContractResources>> {
-        let mut res = self.inner.get_contract_resources(contract_id, block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_contract_resources(contract_id, block);
-        }
-
-        res
+        self.retry(|c| c.get_contract_resources(contract_id, block))
     }
 
     pub fn contract_count(&self, block: Option<Hash>) -> ApiResult<u64> {
-        let mut res = self.inner.contract_count(block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.contract_count(block);
-        }
+        self.retry(|c| c.contract_count(block))
+    }
 
-        res
+    /// Get the storage keys of all contracts, see [RawClient::get_contract_keys].
+    pub fn get_contract_keys(&self, block: Option<Hash>) -> ApiResult<Vec<StorageKey>> {
+        self.retry(|c| c.get_contract_keys(block))
+    }
+
+    /// Get the contracts stored under the given keys, see [RawClient::get_contracts_at].
+    pub fn get_contracts_at(
+        &self,
+        keys: &[StorageKey],
+        block: Option<Hash>,
+    ) -> ApiResult<Vec<(Contract, Option<ContractResources>)>> {
+        self.retry(|c| c.get_contracts_at(keys, block))
     }
 
     pub fn get_farm_payout_address(
@@ -276,96 +627,46 @@ where
         farm_id: u32,
         block: Option<Hash>,
     ) -> ApiResult<Option<String>> {
-        let mut res = self.inner.get_farm_payout_address(farm_id, block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_farm_payout_address(farm_id, block);
-        }
-
-        res
+        self.retry(|c| c.get_farm_payout_address(farm_id, block))
     }
 
     pub fn get_block_by_hash(&self, block_hash: &str) -> ApiResult<Option<Block>> {
-        let mut res = self.inner.get_block_by_hash(block_hash);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_block_by_hash(block_hash);
-        }
-
-        res
+        self.retry(|c| c.get_block_by_hash(block_hash))
     }
 
-    pub fn get_block_events(&self, block: Option<Hash>) -> ApiResult<Vec<TfchainEvent>> {
-        let mut res = self.inner.get_block_events(block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_block_events(block);
-        }
+    pub fn get_header_by_hash(&self, block_hash: &str) -> ApiResult<Option<runtime::Header>> {
+        self.retry(|c| c.get_header_by_hash(block_hash))
+    }
 
-        res
+    pub fn get_block_events(&self, block: Option<Hash>) -> ApiResult<Vec<BlockEvent>> {
+        self.retry(|c| c.get_block_events(block))
     }
 
     pub fn block_timestamp(&self, block: Option<Hash>) -> ApiResult<i64> {
-        let mut res = self.inner.block_timestamp(block);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.block_timestamp(block);
-        }
-
-        res
+        self.retry(|c| c.block_timestamp(block))
     }
 
     pub fn get_hash_at_height(&self, height: BlockNumber) -> ApiResult<Option<Hash>> {
-        let mut res = self.inner.get_hash_at_height(height);
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.get_hash_at_height(height);
-        }
-
-        res
+        self.retry(|c| c.get_hash_at_height(height))
     }
 
+    /// Subscribe to finalized heads. The subscription ends if the connection breaks, see
+    /// [SharedClient::finalized_blocks] for a subscription which recovers from that.
     pub fn finalized_block_headers(&self) -> ApiResult<FinalizedHeadSubscription> {
-        // TODO: what if subscription breaks
-        let mut res = self.inner.finalized_block_headers();
-        for _ in 0..5 {
-            match res {
-                Err(ApiClientError::Disconnected(_)) => {}
-                x => return x,
-            }
-            res = self.inner.finalized_block_headers();
-        }
-
-        res
+        self.retry(|c| c.finalized_block_headers())
     }
 
     // Get the height just past the timestamp. i.e. `block_x_time | ts | block_x+1_time` returns
     // block x+1
     pub fn height_at_timestamp(&self, ts: i64) -> ApiResult<BlockNumber> {
-        // TODO: clean these unwraps, this assumes block 1 always exists (which is the case for
-        // now).
-        // SAFETY: sanity check that ts is smaller than the last height.
+        // This assumes block 1 always exists (which is the case for now).
+        // Sanity check that ts is smaller than the last height.
         let latest_ts = self.block_timestamp(None)? / 1000;
         if latest_ts < ts {
-            panic!(
-                "can't fetch block for future timestamp {} vs latest {}",
-                ts, latest_ts
-            );
+            return Err(Error::FutureTimestamp {
+                timestamp: ts,
+                latest: latest_ts,
+            });
         }
         let mut height = 1;
         let mut last_height = 1;
@@ -395,10 +696,10 @@ where
             }
             // check that the delta is in range
             if (height as i64 + block_delta) < 0 {
-                panic!(
-                    "negative height search (height {} delta {})",
-                    height, block_delta
-                );
+                return Err(Error::InvalidInput(format!(
+                    "timestamp {} is before the first block (height {} delta {})",
+                    ts, height, block_delta
+                )));
             }
 
             // adjust height
@@ -408,6 +709,128 @@ where
             height = (height as i64 + block_delta) as u32;
         }
     }
+
+    /// Execute a call on the [RawClient], retrying it according to the [RetryPolicy] of this
+    /// client. Before every retry, the connection to the chain is reestablished.
+    fn retry<T, F>(&self, f: F) -> ApiResult<T>
+    where
+        F: Fn(&RawClient<P, E>) -> ApiResult<T>,
+    {
+        self.served(f).map(|served| served.value)
+    }
+
+    /// Execute a call on the [RawClient] exactly once, regardless of the [RetryPolicy]. If the
+    /// connection failed, the client fails over so the next call uses a working endpoint.
+    fn once<T, F>(&self, f: F) -> ApiResult<T>
+    where
+        F: FnOnce(&RawClient<P, E>) -> ApiResult<T>,
+    {
+        self.maybe_check_endpoints();
+
+        let (raw, endpoint) = self.current();
+        let res = f(&raw);
+        if let Err(ref err) = res {
+            if matches!(
+                ErrorKind::of(err),
+                ErrorKind::Disconnected | ErrorKind::Connection
+            ) {
+                self.failover(endpoint);
+            }
+        }
+        res
+    }
+
+    /// Submit an extrinsic with `submit`, then read the resulting state with `read`, at the block
+    /// which included the extrinsic. Both calls are retried separately, so a failed read never
+    /// submits the extrinsic again.
+    fn submit_then_read<T, U, S, R>(&self, submit: S, read: R) -> ApiResult<Included<U>>
+    where
+        S: Fn(&RawClient<P, E>) -> ApiResult<Included<T>>,
+        R: Fn(&RawClient<P, E>, Hash) -> ApiResult<U>,
+    {
+        let block = self.retry(submit)?.block;
+        let event = self.retry(|c| read(c, block))?;
+        Ok(Included { block, event })
+    }
+
+    /// Start a health check of the endpoints if the last one is too long ago, and apply the
+    /// result of a finished check. Connecting to every endpoint is slow, so the endpoints are
+    /// probed on a background thread rather than while the caller waits.
+    fn maybe_check_endpoints(&self) {
+        if self.endpoints.urls().len() < 2 {
+            return;
+        }
+        // If the lock is taken, another thread is already handling the health check.
+        let mut health = match self.health.try_lock() {
+            Ok(health) => health,
+            Err(_) => return,
+        };
+        if let Some(ref running) = health.running {
+            match running.try_recv() {
+                Ok(probes) => {
+                    health.running = None;
+                    drop(health);
+                    self.apply_probes(probes);
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                // The check panicked, a new one is started after the next interval.
+                Err(mpsc::TryRecvError::Disconnected) => health.running = None,
+            }
+            return;
+        }
+        if health.last.elapsed() < self.endpoints.health_check_interval() {
+            return;
+        }
+
+        health.last = Instant::now();
+        let (probes_in, probes_out) = mpsc::channel();
+        let urls = self.endpoints.urls().to_vec();
+        let active = self.active.load(Ordering::SeqCst);
+        let signer = self.signer.clone();
+        thread::spawn(move || {
+            // The client might be dropped while the check is running.
+            let _ = probes_in.send(probe_endpoints(&urls, active, signer));
+        });
+        health.running = Some(probes_out);
+    }
+
+    /// Reconnect after a call on the endpoint with the given index failed. Endpoints are tried in
+    /// order, starting with the one after the failed endpoint, and ending with the failed endpoint
+    /// itself. If no endpoint can be reached the old connection is kept, and the next attempt will
+    /// fail again.
+    fn failover(&self, failed: usize) {
+        let urls = self.endpoints.urls();
+        for offset in 1..=urls.len() {
+            let idx = (failed + offset) % urls.len();
+            if let Ok(api) = connect(&urls[idx], self.signer.clone()) {
+                self.switch_to(idx, api);
+                return;
+            }
+        }
+    }
+
+    /// Use the given connection to the endpoint with the given index from now on. Calls which
+    /// are in progress finish on the old connection.
+    fn switch_to(&self, idx: usize, api: Api<P>) {
+        let mut raw = self.inner.write().unwrap();
+        *raw = Arc::new(RawClient {
+            api,
+            _marker: std::marker::PhantomData,
+        });
+        self.active.store(idx, Ordering::SeqCst);
+    }
+}
+
+impl<P, E> Client<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+    E: support::sp_runtime::traits::Member + support::Parameter + DecodeExtrinsic,
+    TfchainEvent: From<E>,
+{
+    pub fn get_block_extrinsics(&self, block: Option<Hash>) -> ApiResult<Vec<Extrinsic>> {
+        self.retry(|c| c.get_block_extrinsics(block))
+    }
 }
 
 pub struct RawClient<P, E>
@@ -426,79 +849,522 @@ where
     E: support::Parameter + sp_runtime::traits::Member,
     TfchainEvent: From<E>,
 {
-    pub fn new(url: String, signer: P) -> RawClient<P, E> {
-        let api = Api::new(url).unwrap().set_signer(signer);
-        RawClient {
+    pub fn new(url: String, signer: P) -> ApiResult<RawClient<P, E>> {
+        let api = connect(&url, Some(signer))?;
+        Ok(RawClient {
             api,
             _marker: std::marker::PhantomData,
+        })
+    }
+
+    /// Sign a call, which can be composed with [compose_call], and submit it. The extrinsic is
+    /// watched until it reaches the given status, or is dropped. Returns a [Receipt] with the
+    /// events emitted by the extrinsic, and whether it succeeded.
+    ///
+    /// Errors which occur after the extrinsic was sent are returned as [Error::StatusUnknown],
+    /// since the extrinsic might still be applied. Those are never retried by a [Client].
+    pub fn submit_and_watch<C>(&self, call: C, until: TxStatus) -> ApiResult<Receipt>
+    where
+        C: Encode + Clone,
+    {
+        let nonces = NonceManager::for_account(&self.signer_account()?);
+        let xt = self.sign(call, &nonces)?;
+        let res = self.watch(&xt, until);
+        // Unless the extrinsic is known to be included, its nonce might never be used, and later
+        // extrinsics would wait for it forever.
+        if res.is_err() {
+            nonces.resync();
         }
+        res
     }
 
-    pub fn create_twin(&self, ip: &str) -> ApiResult<Option<Hash>> {
-        let xt: UncheckedExtrinsicV4<_> =
-            compose_extrinsic!(self.api.clone(), "TfgridModule", "create_twin", ip);
-        self.api.send_extrinsic(xt.hex_encode(), XtStatus::Ready)
+    /// Send a signed extrinsic, and build its [Receipt] once it reaches the given status.
+    fn watch<C>(&self, xt: &UncheckedExtrinsicV4<C>, until: TxStatus) -> ApiResult<Receipt>
+    where
+        C: Encode + Clone,
+    {
+        let encoded = xt.encode();
+        let unknown = |e: Error| Error::StatusUnknown {
+            extrinsic: Hash::from(sp_core::blake2_256(&encoded)),
+            reason: e.to_string(),
+        };
+        let block = match self.send(xt, XtStatus::InBlock) {
+            Ok(Some(block)) => block,
+            Ok(None) => {
+                return Err(Error::Dropped(
+                    "extrinsic was not included in a block".into(),
+                ))
+            }
+            // The node rejected the extrinsic, so it never entered the transaction pool.
+            Err(e @ Error::Transport(ApiClientError::RpcClient(_))) => return Err(e),
+            Err(e) => return Err(unknown(e)),
+        };
+        self.receipt(&encoded, block, until).map_err(|e| match e {
+            Error::Dropped(_) => e,
+            e => unknown(e),
+        })
     }
 
-    pub fn get_twin_by_id(&self, id: u32) -> ApiResult<Twin> {
-        let twin: Twin = self
+    /// Build the [Receipt] of an encoded extrinsic which was included in the given block, after
+    /// waiting until it reaches the given status.
+    fn receipt(&self, encoded: &[u8], block: Hash, until: TxStatus) -> ApiResult<Receipt> {
+        let header: runtime::Header = self
             .api
-            .get_storage_map("TfgridModule", "Twins", id, None)
-            .unwrap()
-            .or_else(|| Some(Twin::default()))
-            .unwrap();
+            .get_header(Some(block))?
+            .ok_or_else(|| Error::NotFound(format!("header of block {:?}", block)))?;
+        if until == TxStatus::Finalized {
+            self.wait_finalized(header.number, block)?;
+        }
 
-        Ok(twin)
+        let extrinsic_index = self.extrinsic_index(block, encoded)?;
+        let mut outcome = None;
+        let mut events = Vec::new();
+        for record in self.get_block_events(Some(block))? {
+            if record.phase != Phase::ApplyExtrinsic(extrinsic_index) {
+                continue;
+            }
+            let event = record.event;
+            match &event {
+                TfchainEvent::System(SystemEvent::ExtrinsicSuccess(_)) => {
+                    outcome = Some(Outcome::Success)
+                }
+                TfchainEvent::System(SystemEvent::ExtrinsicFailed(error, _)) => {
+                    outcome = Some(Outcome::Failed(error.clone()))
+                }
+                _ => {}
+            }
+            events.push(event);
+        }
+        let outcome = outcome.ok_or_else(|| {
+            Error::Decode(format!(
+                "no outcome found for extrinsic {} in block {:?}",
+                extrinsic_index, block
+            ))
+        })?;
+
+        Ok(Receipt {
+            status: until,
+            block,
+            height: header.number,
+            extrinsic_index,
+            events,
+            outcome,
+        })
+    }
+
+    pub fn create_twin(&self, ip: &str) -> ApiResult<Receipt> {
+        let call = compose_call!(self.api.metadata.clone(), "TfgridModule", "create_twin", ip);
+        self.submit_and_watch(call, TxStatus::InBlock)
+    }
+
+    pub fn get_twin_by_id(&self, id: u32, block: Option<Hash>) -> ApiResult<Option<Twin>> {
+        Ok(self
+            .api
+            .get_storage_map("TfgridModule", "Twins", id, block)?)
+    }
+
+    /// Update the IP of the twin of the signer. Returns the [TFGridEvent::TwinUpdated] event.
+    pub fn update_twin(&self, ip: &str) -> ApiResult<Included<TFGridEvent>> {
+        let account = self.signer_account()?;
+        let call = compose_call!(self.api.metadata.clone(), "TfgridModule", "update_twin", ip);
+        self.submit_and_find(call, |e| {
+            tfgrid_event(e).filter(
+                |e| matches!(e, TFGridEvent::TwinUpdated(twin) if twin.account_id == account),
+            )
+        })
     }
 
-    pub fn create_farm(&self, name: &str) -> ApiResult<Option<Hash>> {
-        let xt: UncheckedExtrinsicV4<_> =
-            compose_extrinsic!(self.api.clone(), "TfgridModule", "create_farm", name);
-        self.api.send_extrinsic(xt.hex_encode(), XtStatus::InBlock)
+    /// Delete a twin owned by the signer. Returns the [TFGridEvent::TwinDeleted] event.
+    pub fn delete_twin(&self, twin_id: u32) -> ApiResult<Included<TFGridEvent>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "delete_twin",
+            twin_id
+        );
+        self.submit_and_find(call, |e| {
+            tfgrid_event(e).filter(|e| matches!(e, TFGridEvent::TwinDeleted(id) if *id == twin_id))
+        })
+    }
+
+    /// Link an entity to a twin owned by the signer. The signature is the signature of the
+    /// entity over the twin and entity id. Returns the [TFGridEvent::TwinEntityStored] event.
+    pub fn add_twin_entity(
+        &self,
+        twin_id: u32,
+        entity_id: u32,
+        signature: &[u8],
+    ) -> ApiResult<Included<TFGridEvent>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "add_twin_entity",
+            twin_id,
+            entity_id,
+            signature
+        );
+        self.submit_and_find(call, |e| {
+            tfgrid_event(e).filter(|e| {
+                matches!(e, TFGridEvent::TwinEntityStored(tid, eid, _)
+                    if *tid == twin_id && *eid == entity_id)
+            })
+        })
+    }
+
+    /// Remove the link between an entity and a twin owned by the signer. Returns the
+    /// [TFGridEvent::TwinEntityRemoved] event.
+    pub fn delete_twin_entity(
+        &self,
+        twin_id: u32,
+        entity_id: u32,
+    ) -> ApiResult<Included<TFGridEvent>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "delete_twin_entity",
+            twin_id,
+            entity_id
+        );
+        self.submit_and_find(call, |e| {
+            tfgrid_event(e).filter(|e| {
+                matches!(e, TFGridEvent::TwinEntityRemoved(tid, eid)
+                    if *tid == twin_id && *eid == entity_id)
+            })
+        })
+    }
+
+    pub fn create_farm(&self, name: &str) -> ApiResult<Receipt> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "create_farm",
+            name
+        );
+        self.submit_and_watch(call, TxStatus::InBlock)
+    }
+
+    /// Update the name and pricing policy of a farm owned by the signer. Returns the updated
+    /// farm.
+    pub fn update_farm(
+        &self,
+        farm_id: u32,
+        name: &str,
+        pricing_policy_id: u32,
+    ) -> ApiResult<Included<Farm>> {
+        validation::farm_name(name)?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "update_farm",
+            farm_id,
+            name,
+            pricing_policy_id
+        );
+        self.submit_and_find(call, farm_updated(farm_id))
+    }
+
+    /// Add a public IP to a farm owned by the signer. The IP must be in CIDR notation, and the
+    /// gateway must be in the same subnet. Returns the updated farm.
+    pub fn add_farm_ip(&self, farm_id: u32, ip: &str, gateway: &str) -> ApiResult<Included<Farm>> {
+        validation::ipv4_gateway(ip, gateway)?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "add_farm_ip",
+            farm_id,
+            ip,
+            gateway
+        );
+        self.submit_and_find(call, farm_updated(farm_id))
+    }
+
+    /// Remove a public IP, in CIDR notation, from a farm owned by the signer. Returns the updated
+    /// farm.
+    pub fn remove_farm_ip(&self, farm_id: u32, ip: &str) -> ApiResult<Included<Farm>> {
+        validation::ipv4_cidr(ip)?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "remove_farm_ip",
+            farm_id,
+            ip
+        );
+        self.submit_and_find(call, farm_updated(farm_id))
+    }
+
+    /// Set the stellar address farming rewards of a farm owned by the signer are paid out to.
+    /// Returns the farm as it is after the address is registered.
+    pub fn add_stellar_payout_v2address(
+        &self,
+        farm_id: u32,
+        address: &str,
+    ) -> ApiResult<Included<Farm>> {
+        let registered = self.register_stellar_payout_v2address(farm_id, address)?;
+        let farm = self
+            .get_farm_by_id(farm_id, Some(registered.block))?
+            .ok_or_else(|| Error::NotFound(format!("farm {}", farm_id)))?;
+        Ok(Included {
+            block: registered.block,
+            event: farm,
+        })
+    }
+
+    /// Set the stellar address farming rewards of a farm owned by the signer are paid out to.
+    /// Returns the id of the farm, from the event emitted when the address is registered.
+    pub fn register_stellar_payout_v2address(
+        &self,
+        farm_id: u32,
+        address: &str,
+    ) -> ApiResult<Included<u32>> {
+        validation::stellar_address(address)?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "add_stellar_payout_v2address",
+            farm_id,
+            address
+        );
+        self.submit_and_find(call, |e| match tfgrid_event(e)? {
+            TFGridEvent::FarmPayoutV2AddressRegistered(id, _) if id == farm_id => Some(id),
+            _ => None,
+        })
+    }
+
+    /// Mark a farm as dedicated, or undo this. Only nodes in a dedicated farm can be rented. This
+    /// requires the signer to be the root key. Returns the updated farm.
+    pub fn set_farm_dedicated(&self, farm_id: u32, dedicated: bool) -> ApiResult<Included<Farm>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "set_farm_dedicated",
+            farm_id,
+            dedicated
+        );
+        self.submit_and_find(call, farm_updated(farm_id))
+    }
+
+    /// Delete a farm owned by the signer. Returns the id of the deleted farm.
+    pub fn delete_farm(&self, farm_id: u32) -> ApiResult<Included<u32>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "delete_farm",
+            farm_id
+        );
+        self.submit_and_find(call, |e| match tfgrid_event(e)? {
+            TFGridEvent::FarmDeleted(id) if id == farm_id => Some(id),
+            _ => None,
+        })
     }
 
     pub fn get_farm_by_id(&self, id: u32, block: Option<Hash>) -> ApiResult<Option<Farm>> {
-        self.api.get_storage_map("TfgridModule", "Farms", id, block)
+        Ok(self
+            .api
+            .get_storage_map("TfgridModule", "Farms", id, block)?)
     }
 
     pub fn get_farm_id_by_name(&self, name: &str) -> ApiResult<u32> {
-        let farm_id: u32 = self
+        // A farm ID of 0 indicates there is no farm with the given name.
+        Ok(self
             .api
-            .get_storage_map("TfgridModule", "FarmIdByName", name, None)
-            .unwrap()
-            .or_else(|| Some(0))
-            .unwrap();
-
-        Ok(farm_id)
+            .get_storage_map("TfgridModule", "FarmIdByName", name, None)?
+            .unwrap_or(0))
     }
 
     pub fn farm_count(&self, block: Option<Hash>) -> ApiResult<u32> {
-        // Safety: farmID is initialized in genesis so this value is always set.
+        // farmID is initialized in genesis so this value is always set.
         self.api
-            .get_storage_value("TfgridModule", "FarmID", block)
-            .map(|i| i.unwrap())
+            .get_storage_value("TfgridModule", "FarmID", block)?
+            .ok_or_else(|| Error::NotFound("TfgridModule::FarmID".into()))
     }
 
-    pub fn get_account_free_balance(&self, account: &AccountId32) -> ApiResult<AccountData> {
+    /// Get the storage keys of all farms, ordered by farm id. The values can be fetched in bulk
+    /// with [get_farms_at](RawClient::get_farms_at).
+    pub fn get_farm_keys(&self, block: Option<Hash>) -> ApiResult<Vec<StorageKey>> {
+        self.storage_map_keys::<u32>("TfgridModule", "Farms", block)
+    }
+
+    /// Get the farms stored under the given keys, in the order of the keys. Keys without a farm
+    /// are skipped.
+    pub fn get_farms_at(&self, keys: &[StorageKey], block: Option<Hash>) -> ApiResult<Vec<Farm>> {
+        self.storage_values(keys, block, |value| decode_value(value, "farm"))
+    }
+
+    pub fn get_account_free_balance(
+        &self,
+        account: &AccountId32,
+        block: Option<Hash>,
+    ) -> ApiResult<AccountData> {
         let info: AccountInfo = self
             .api
-            .get_storage_map("System", "Account", account, None)?
-            .or_else(|| Some(AccountInfo::default()))
-            .unwrap();
+            .get_storage_map("System", "Account", account, block)?
+            .unwrap_or_default();
 
         Ok(info.data)
     }
 
+    /// Transfer an amount from the signer to another account. The signer account is allowed to
+    /// be reaped if its balance drops below the existential deposit. Returns the
+    /// [BalanceEvent::Transfer] event.
+    pub fn transfer(&self, to: &AccountId32, amount: Tft) -> ApiResult<Included<BalanceEvent>> {
+        self.balance_transfer("transfer", to, amount)
+    }
+
+    /// Transfer an amount from the signer to another account. Fails if the balance of the signer
+    /// would drop below the existential deposit. Returns the [BalanceEvent::Transfer] event.
+    pub fn transfer_keep_alive(
+        &self,
+        to: &AccountId32,
+        amount: Tft,
+    ) -> ApiResult<Included<BalanceEvent>> {
+        self.balance_transfer("transfer_keep_alive", to, amount)
+    }
+
+    /// Register a new node in a farm, with the twin of the signer as node twin. Only the farm id,
+    /// resources, location, country, city, interfaces, secure boot and virtualized flags, and
+    /// serial number of `node` are used, all other fields are set by the chain. Returns the
+    /// created node.
+    pub fn create_node(&self, node: &Node) -> ApiResult<Included<Node>> {
+        let twin_id = self.signer_twin_id()?;
+        let node: tfchain_support::types::Node = node.clone().into();
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "create_node",
+            node.farm_id,
+            node.resources,
+            node.location,
+            node.country,
+            node.city,
+            node.interfaces,
+            node.secure_boot,
+            node.virtualized,
+            node.serial_number
+        );
+        self.submit_and_find(call, |e| match tfgrid_event(e)? {
+            TFGridEvent::NodeStored(node) if node.twin_id == twin_id => Some(node),
+            _ => None,
+        })
+    }
+
+    /// Update a node owned by the signer. The same fields as in [RawClient::create_node] are
+    /// used, and the node to update is selected by the id of `node`. Returns the updated node.
+    pub fn update_node(&self, node: &Node) -> ApiResult<Included<Node>> {
+        let node_id = node.id;
+        let node: tfchain_support::types::Node = node.clone().into();
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "update_node",
+            node.id,
+            node.farm_id,
+            node.resources,
+            node.location,
+            node.country,
+            node.city,
+            node.interfaces,
+            node.secure_boot,
+            node.virtualized,
+            node.serial_number
+        );
+        self.submit_and_find(call, |e| match tfgrid_event(e)? {
+            TFGridEvent::NodeUpdated(node) if node.id == node_id => Some(node),
+            _ => None,
+        })
+    }
+
+    /// Delete a node from the farm it is in. The signer must own the farm. Returns the id of the
+    /// deleted node.
+    pub fn delete_node(&self, node_id: u32) -> ApiResult<Included<u32>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "delete_node_farm",
+            node_id
+        );
+        self.submit_and_find(call, |e| match tfgrid_event(e)? {
+            TFGridEvent::NodeDeleted(id) if id == node_id => Some(id),
+            _ => None,
+        })
+    }
+
+    /// Set the public config of a node in a farm owned by the signer. Returns the public config
+    /// as stored on chain.
+    pub fn add_node_public_config(
+        &self,
+        farm_id: u32,
+        node_id: u32,
+        public_config: &PublicConfig,
+    ) -> ApiResult<Included<PublicConfig>> {
+        validation::ipv4_gateway(&public_config.ipv4, &public_config.gw4)?;
+        let config: tfchain_support::types::PublicConfig = public_config.clone().into();
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "add_node_public_config",
+            farm_id,
+            node_id,
+            config
+        );
+        self.submit_and_find(call, |e| match tfgrid_event(e)? {
+            TFGridEvent::NodePublicConfigStored(id, config) if id == node_id => Some(config),
+            _ => None,
+        })
+    }
+
+    /// Report the uptime, in seconds, of the node of the signer. Returns the
+    /// [TFGridEvent::NodeUptimeReported] event.
+    pub fn report_uptime(&self, uptime: u64) -> ApiResult<Included<TFGridEvent>> {
+        let twin_id = self.signer_twin_id()?;
+        let node_id: u32 = self
+            .api
+            .get_storage_map("TfgridModule", "NodeIdByTwinID", twin_id, None)?
+            .ok_or_else(|| Error::InvalidInput(format!("twin {} has no node", twin_id)))?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "report_uptime",
+            uptime
+        );
+        self.submit_and_find(call, |e| {
+            tfgrid_event(e).filter(
+                |e| matches!(e, TFGridEvent::NodeUptimeReported(id, _, _) if *id == node_id),
+            )
+        })
+    }
+
+    /// Set the certification of a node. The signer must be an allowed node certifier. Returns
+    /// the certification set on the node.
+    pub fn set_node_certification(
+        &self,
+        node_id: u32,
+        certification: NodeCertification,
+    ) -> ApiResult<Included<NodeCertification>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "TfgridModule",
+            "set_node_certification",
+            node_id,
+            tfchain_support::types::NodeCertification::from(certification)
+        );
+        self.submit_and_find(call, |e| match tfgrid_event(e)? {
+            TFGridEvent::NodeCertificationSet(id, nc) if id == node_id => Some(nc),
+            _ => None,
+        })
+    }
+
     pub fn get_node_by_id(&self, node_id: u32, block: Option<Hash>) -> ApiResult<Option<Node>> {
-        // Try to decode all known node types here.
-        let res = self.api.get_storage_map::<_, tfchain_support::types::Node>(
+        // Try to decode all known node types here. Only fall back to the legacy type if the
+        // value could not be decoded, other errors are returned as is so they can be retried.
+        match self.api.get_storage_map::<_, tfchain_support::types::Node>(
             "TfgridModule",
             "Nodes",
             node_id,
             block,
-        );
-        if res.is_ok() {
-            return Ok(res.unwrap().map(Node::from));
+        ) {
+            Ok(node) => return Ok(node.map(Node::from)),
+            Err(ApiClientError::StorageValueDecode(_)) => {}
+            Err(e) => return Err(e.into()),
         }
         self.api
             .get_storage_map::<_, pallet_tfgrid_legacy::types::Node>(
@@ -508,13 +1374,187 @@ where
                 block,
             )
             .map(|pr| pr.map(Node::from))
+            .map_err(|e| runtime_mismatch(e, &format!("node {}", node_id)))
     }
 
     pub fn node_count(&self, block: Option<Hash>) -> ApiResult<u32> {
-        // Safety: nodeID is initialized in genesis so this value is always set.
+        // nodeID is initialized in genesis so this value is always set.
         self.api
-            .get_storage_value("TfgridModule", "NodeID", block)
-            .map(|i| i.unwrap())
+            .get_storage_value("TfgridModule", "NodeID", block)?
+            .ok_or_else(|| Error::NotFound("TfgridModule::NodeID".into()))
+    }
+
+    /// Get the storage keys of all nodes, ordered by node id. The values can be fetched in bulk
+    /// with [get_nodes_at](RawClient::get_nodes_at).
+    pub fn get_node_keys(&self, block: Option<Hash>) -> ApiResult<Vec<StorageKey>> {
+        self.storage_map_keys::<u32>("TfgridModule", "Nodes", block)
+    }
+
+    /// Get the nodes stored under the given keys, in the order of the keys. Keys without a node
+    /// are skipped.
+    pub fn get_nodes_at(&self, keys: &[StorageKey], block: Option<Hash>) -> ApiResult<Vec<Node>> {
+        self.storage_values(keys, block, |value| {
+            // Try to decode all known node types here.
+            if let Ok(node) = tfchain_support::types::Node::decode(&mut &value[..]) {
+                return Ok(Node::from(node));
+            }
+            pallet_tfgrid_legacy::types::Node::decode(&mut &value[..])
+                .map(Node::from)
+                .map_err(|e| Error::RuntimeMismatch(format!("could not decode node: {}", e)))
+        })
+    }
+
+    /// Create a contract for a deployment on a node, as the twin of the signer. If `public_ips`
+    /// is not 0, that many public IPs of the farm of the node are reserved for the contract.
+    /// Returns the created contract, together with the reserved IPs.
+    pub fn create_node_contract(
+        &self,
+        node_id: u32,
+        deployment_data: &[u8],
+        deployment_hash: &[u8],
+        public_ips: u32,
+    ) -> ApiResult<Included<NodeContractCreated>> {
+        let twin_id = self.signer_twin_id()?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "SmartContractModule",
+            "create_node_contract",
+            node_id,
+            deployment_data,
+            deployment_hash,
+            public_ips
+        );
+        let included = self.submit(call)?;
+        let mut reserved = BTreeMap::new();
+        let mut created = None;
+        for event in included.event.into_iter().filter_map(smart_contract_event) {
+            match event {
+                SmartContractEvent::IPsReserved(contract_id, ips) => {
+                    reserved.insert(contract_id, ips);
+                }
+                SmartContractEvent::ContractCreated(contract)
+                    if contract.twin_id == twin_id
+                        && matches!(&contract.contract_type, ContractData::NodeContract(nc)
+                            if nc.node_id == node_id && nc.deployment_hash == deployment_hash) =>
+                {
+                    created = Some(contract);
+                }
+                _ => {}
+            }
+        }
+        let contract = created.ok_or_else(|| not_found_in(included.block))?;
+        Ok(Included {
+            block: included.block,
+            event: NodeContractCreated {
+                reserved_ips: reserved.remove(&contract.contract_id).unwrap_or_default(),
+                contract,
+            },
+        })
+    }
+
+    /// Update the deployment of a node contract owned by the signer. Returns the updated
+    /// contract.
+    pub fn update_node_contract(
+        &self,
+        contract_id: u64,
+        deployment_data: &[u8],
+        deployment_hash: &[u8],
+    ) -> ApiResult<Included<Contract>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "SmartContractModule",
+            "update_node_contract",
+            contract_id,
+            deployment_data,
+            deployment_hash
+        );
+        self.submit_and_find(call, |e| match smart_contract_event(e)? {
+            SmartContractEvent::ContractUpdated(c) if c.contract_id == contract_id => Some(c),
+            _ => None,
+        })
+    }
+
+    /// Reserve a name for the twin of the signer, e.g. to be used as gateway domain. Returns the
+    /// created contract.
+    pub fn create_name_contract(&self, name: &str) -> ApiResult<Included<Contract>> {
+        let twin_id = self.signer_twin_id()?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "SmartContractModule",
+            "create_name_contract",
+            name
+        );
+        self.submit_and_find(
+            call,
+            contract_created(
+                twin_id,
+                |cd| matches!(cd, ContractData::NameContract(nc) if nc.name == name),
+            ),
+        )
+    }
+
+    /// Rent a full node, which must be in a dedicated farm, for the twin of the signer. Returns
+    /// the created contract.
+    pub fn create_rent_contract(&self, node_id: u32) -> ApiResult<Included<Contract>> {
+        let twin_id = self.signer_twin_id()?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "SmartContractModule",
+            "create_rent_contract",
+            node_id
+        );
+        self.submit_and_find(
+            call,
+            contract_created(
+                twin_id,
+                |cd| matches!(cd, ContractData::RentContract(rc) if rc.node_id == node_id),
+            ),
+        )
+    }
+
+    /// Cancel a contract owned by the signer. Returns the event signaling the cancellation, which
+    /// depends on the type of the contract.
+    pub fn cancel_contract(&self, contract_id: u64) -> ApiResult<Included<SmartContractEvent>> {
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "SmartContractModule",
+            "cancel_contract",
+            contract_id
+        );
+        self.submit_and_find(call, |e| {
+            smart_contract_event(e).filter(|e| match e {
+                SmartContractEvent::NodeContractCanceled(id, _, _)
+                | SmartContractEvent::NameContractCanceled(id)
+                | SmartContractEvent::RentContractCancelled(id) => *id == contract_id,
+                _ => false,
+            })
+        })
+    }
+
+    /// Submit consumption reports of the node of the signer. Reports for the same contract must
+    /// have strictly increasing timestamps. Reports are submitted in batches of at most
+    /// [MAX_REPORTS_PER_EXTRINSIC], one extrinsic per batch. Returns, for every batch, the
+    /// reports accepted by the chain. If a batch fails, no further batches are submitted, and
+    /// the [BatchError] holds the batches which were already included.
+    pub fn add_reports(&self, reports: &[Consumption]) -> BatchResult<Consumption> {
+        submit_batches(
+            reports,
+            |r| (r.contract_id, r.timestamp),
+            |batch| self.add_reports_batch(batch),
+        )
+    }
+
+    /// Submit NRU consumption reports of the node of the signer. Reports for the same contract
+    /// must have strictly increasing timestamps. Reports are submitted in batches of at most
+    /// [MAX_REPORTS_PER_EXTRINSIC], one extrinsic per batch. Returns, for every batch, the
+    /// reports accepted by the chain. If a batch fails, no further batches are submitted, and
+    /// the [BatchError] holds the batches which were already included.
+    pub fn add_nru_reports(&self, reports: &[NruConsumption]) -> BatchResult<NruConsumption> {
+        submit_batches(
+            reports,
+            |r| (r.contract_id, r.timestamp),
+            |batch| self.add_nru_reports_batch(batch),
+        )
     }
 
     pub fn get_contract_by_id(
@@ -522,8 +1562,9 @@ where
         contract_id: u64,
         block: Option<Hash>,
     ) -> ApiResult<Option<Contract>> {
-        self.api
-            .get_storage_map("SmartContractModule", "Contracts", contract_id, block)
+        Ok(self
+            .api
+            .get_storage_map("SmartContractModule", "Contracts", contract_id, block)?)
     }
 
     pub fn get_contract_resources(
@@ -531,19 +1572,66 @@ where
         contract_id: u64,
         block: Option<Hash>,
     ) -> ApiResult<Option<ContractResources>> {
-        self.api.get_storage_map(
+        Ok(self.api.get_storage_map(
             "SmartContractModule",
             "NodeContractResources",
             contract_id,
             block,
-        )
+        )?)
     }
 
     pub fn contract_count(&self, block: Option<Hash>) -> ApiResult<u64> {
-        // Safety: contractID is initialized in genesis so this value is always set.
-        self.api
-            .get_storage_value("SmartContractModule", "ContractID", block)
-            .map(|i| i.unwrap_or(0))
+        // contractID is initialized in genesis so this value is always set.
+        Ok(self
+            .api
+            .get_storage_value("SmartContractModule", "ContractID", block)?
+            .unwrap_or(0))
+    }
+
+    /// Get the storage keys of all contracts, ordered by contract id. The values can be fetched
+    /// in bulk with [get_contracts_at](RawClient::get_contracts_at).
+    pub fn get_contract_keys(&self, block: Option<Hash>) -> ApiResult<Vec<StorageKey>> {
+        self.storage_map_keys::<u64>("SmartContractModule", "Contracts", block)
+    }
+
+    /// Get the contracts stored under the given keys, in the order of the keys, together with
+    /// their resources. Keys without a contract are skipped.
+    pub fn get_contracts_at(
+        &self,
+        keys: &[StorageKey],
+        block: Option<Hash>,
+    ) -> ApiResult<Vec<(Contract, Option<ContractResources>)>> {
+        let contracts: Vec<Contract> =
+            self.storage_values(keys, block, |value| decode_value(value, "contract"))?;
+        let keys = contracts
+            .iter()
+            .map(|contract| {
+                self.api
+                    .metadata
+                    .storage_map_key::<u64, ContractResources>(
+                        "SmartContractModule",
+                        "NodeContractResources",
+                        contract.contract_id,
+                    )
+                    .map_err(|e| {
+                        Error::RuntimeMismatch(format!(
+                            "no storage map SmartContractModule::NodeContractResources: {:?}",
+                            e
+                        ))
+                    })
+            })
+            .collect::<ApiResult<Vec<_>>>()?;
+        let resources = self
+            .query_storage_at(&keys, block)?
+            .into_iter()
+            .map(|value| {
+                value
+                    .map(|v| decode_value(&v, "contract resources"))
+                    .transpose()
+            })
+            .collect::<ApiResult<Vec<_>>>()?;
+
+        Ok(contracts.into_iter().zip(resources).collect())
     }
 
     pub fn get_farm_payout_address(
@@ -551,39 +1639,60 @@ where
         farm_id: u32,
         block: Option<Hash>,
     ) -> ApiResult<Option<String>> {
-        self.api.get_storage_map(
+        Ok(self.api.get_storage_map(
             "TfgridModule",
             "FarmPayoutV2AddressByFarmID",
             farm_id,
             block,
-        )
+        )?)
     }
 
     pub fn get_block_by_hash(&self, block_hash: &str) -> ApiResult<Option<Block>> {
-        // TODO: Very happy path
-        let mut raw_hash = [0; 32];
-        hex::decode_to_slice(&block_hash[2..], &mut raw_hash).unwrap();
-        let hash = Hash::from(raw_hash);
-        self.api.get_block(Some(hash))
+        let hash = parse_hash(block_hash)?;
+        Ok(self.api.get_block(Some(hash))?)
     }
 
-    pub fn get_block_events(&self, block: Option<Hash>) -> ApiResult<Vec<TfchainEvent>> {
+    /// Get the header of the block with the given hash. Unlike
+    /// [get_block_by_hash](Self::get_block_by_hash) the extrinsics are not decoded, so this works
+    /// for blocks of every runtime version.
+    pub fn get_header_by_hash(&self, block_hash: &str) -> ApiResult<Option<runtime::Header>> {
+        let hash = parse_hash(block_hash)?;
+        Ok(self.api.get_header(Some(hash))?)
+    }
+
+    /// Get the events of a block, together with the phase in which they were emitted.
+    pub fn get_block_events(&self, block: Option<Hash>) -> ApiResult<Vec<BlockEvent>> {
+        // An empty event list is not stored, so a missing value means there are no events.
         let events: Vec<system::EventRecord<E, Hash>> = self
             .api
-            .get_storage_value("System", "Events", block)?
-            .unwrap();
+            .get_storage_value("System", "Events", block)
+            .map_err(|e| runtime_mismatch(e, "block events"))?
+            .unwrap_or_default();
 
+        let events: Vec<_> = events
+            .into_iter()
+            .map(|record| BlockEvent {
+                phase: record.phase.into(),
+                event: TfchainEvent::from(record.event),
+                topics: record.topics,
+            })
+            .collect();
+        if !events.iter().any(|e| has_error_names(&e.event)) || !self.metadata_applies(block) {
+            return Ok(events);
+        }
         Ok(events
             .into_iter()
-            .map(|e| TfchainEvent::from(e.event))
+            .map(|e| BlockEvent {
+                event: self.resolve_event(e.event),
+                ..e
+            })
             .collect())
     }
 
     pub fn block_timestamp(&self, block: Option<Hash>) -> ApiResult<i64> {
-        Ok(self
-            .api
+        self.api
             .get_storage_value("Timestamp", "Now", block)?
-            .unwrap())
+            .ok_or_else(|| Error::NotFound("Timestamp::Now".into()))
     }
 
     pub fn get_hash_at_height(&self, height: BlockNumber) -> ApiResult<Option<Hash>> {
@@ -591,25 +1700,810 @@ where
         let resp = self.api.get_request(req.to_string())?;
         match resp {
             None => Ok(None),
-            Some(hash_str) => {
-                let mut raw_hash = [0; 32];
-                // TODO: this could be improved
-                hex::decode_to_slice(&hash_str[3..67], &mut raw_hash).unwrap();
-                Ok(Some(Hash::from(raw_hash)))
+            Some(resp) => {
+                // The response is the raw json value, i.e. a quoted hex string.
+                let hash_str: String = serde_json::from_str(&resp)
+                    .map_err(|e| Error::Decode(format!("invalid block hash response: {}", e)))?;
+                Ok(Some(parse_hash(&hash_str)?))
             }
         }
     }
 
+    /// Get the height of the latest finalized block.
+    pub fn finalized_height(&self) -> ApiResult<BlockNumber> {
+        finalized_height(&self.api)
+    }
+
+    /// Get the spec version of the runtime at the given block.
+    pub fn spec_version(&self, block: Option<Hash>) -> ApiResult<u32> {
+        let req = json!({
+            "method": "state_getRuntimeVersion",
+            "params": [block],
+            "jsonrpc": "2.0",
+            "id": "1",
+        });
+        let resp = self
+            .api
+            .get_request(req.to_string())?
+            .ok_or_else(|| Error::NotFound("runtime version".into()))?;
+        let version: serde_json::Value = serde_json::from_str(&resp)
+            .map_err(|e| Error::Decode(format!("invalid runtime version response: {}", e)))?;
+        version["specVersion"]
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| Error::Decode(format!("runtime version without spec version: {}", resp)))
+    }
+
+    /// Get all keys of a storage map, ordered by the id they are stored under. The keys are
+    /// listed with `state_getKeysPaged`, which returns them in the order of their hashes.
+    fn storage_map_keys<K>(
+        &self,
+        module: &'static str,
+        storage: &'static str,
+        block: Option<Hash>,
+    ) -> ApiResult<Vec<StorageKey>>
+    where
+        K: Decode + Ord,
+    {
+        let prefix = self
+            .api
+            .metadata
+            .storage_map_key_prefix(module, storage)
+            .map_err(|e| {
+                Error::RuntimeMismatch(format!("no storage map {}::{}: {:?}", module, storage, e))
+            })?;
+        let mut keys = Vec::new();
+        loop {
+            let page = self.storage_keys_paged(&prefix, STORAGE_PAGE_SIZE, keys.last(), block)?;
+            let last = page.len() < STORAGE_PAGE_SIZE as usize;
+            keys.extend(page);
+            if last {
+                break;
+            }
+        }
+
+        let mut ids = keys
+            .into_iter()
+            .map(|key| Ok((key_id::<K>(&key)?, key)))
+            .collect::<ApiResult<Vec<_>>>()?;
+        ids.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(ids.into_iter().map(|(_, key)| key).collect())
+    }
+
+    /// Get the values stored under the given keys, decoding every value with `decode`. The
+    /// values are fetched with a single `state_queryStorageAt` call.
+    fn storage_values<V, F>(
+        &self,
+        keys: &[StorageKey],
+        block: Option<Hash>,
+        decode: F,
+    ) -> ApiResult<Vec<V>>
+    where
+        F: Fn(&[u8]) -> ApiResult<V>,
+    {
+        self.query_storage_at(keys, block)?
+            .into_iter()
+            // Without a block, a value can be removed after its key was listed.
+            .flatten()
+            .map(|value| decode(&value))
+            .collect()
+    }
+
+    /// Get at most `count` storage keys starting with `prefix`, in storage order. If `start_key`
+    /// is set, only keys after it are returned.
+    fn storage_keys_paged(
+        &self,
+        prefix: &StorageKey,
+        count: u32,
+        start_key: Option<&StorageKey>,
+        block: Option<Hash>,
+    ) -> ApiResult<Vec<StorageKey>> {
+        let req = json!({
+            "method": "state_getKeysPaged",
+            "params": [hex_key(prefix), count, start_key.map(hex_key), block],
+            "jsonrpc": "2.0",
+            "id": "1",
+        });
+        let resp = self
+            .api
+            .get_request(req.to_string())?
+            .ok_or_else(|| Error::NotFound(format!("storage keys {}", hex_key(prefix))))?;
+        let keys: Vec<String> = serde_json::from_str(&resp)
+            .map_err(|e| Error::Decode(format!("invalid storage keys response: {}", e)))?;
+        keys.iter()
+            .map(|key| parse_hex(key).map(StorageKey))
+            .collect()
+    }
+
+    /// Get the values of multiple storage keys in a single call. The values are returned in the
+    /// order of the keys, with `None` for keys without a value.
+    fn query_storage_at(
+        &self,
+        keys: &[StorageKey],
+        block: Option<Hash>,
+    ) -> ApiResult<Vec<Option<Vec<u8>>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let req = json!({
+            "method": "state_queryStorageAt",
+            "params": [keys.iter().map(hex_key).collect::<Vec<_>>(), block],
+            "jsonrpc": "2.0",
+            "id": "1",
+        });
+        let resp = self
+            .api
+            .get_request(req.to_string())?
+            .ok_or_else(|| Error::NotFound("storage values".into()))?;
+        let change_sets: serde_json::Value = serde_json::from_str(&resp)
+            .map_err(|e| Error::Decode(format!("invalid storage values response: {}", e)))?;
+
+        // The response is a list of change sets, each holding `[key, value]` pairs.
+        let mut values = BTreeMap::new();
+        let changes = change_sets
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|set| set["changes"].as_array())
+            .flatten();
+        for change in changes {
+            let key = change[0]
+                .as_str()
+                .ok_or_else(|| Error::Decode(format!("invalid storage change: {}", change)))?;
+            let value = change[1].as_str().map(parse_hex).transpose()?;
+            values.insert(parse_hex(key)?, value);
+        }
+
+        Ok(keys
+            .iter()
+            .map(|key| values.remove(&key.0).flatten())
+            .collect())
+    }
+
     pub fn finalized_block_headers(&self) -> ApiResult<FinalizedHeadSubscription> {
         let (heads_in, heads_out) = mpsc::channel();
         self.api.subscribe_finalized_heads(heads_in)?;
 
         Ok(FinalizedHeadSubscription { stream: heads_out })
     }
+
+    /// The account of the signer of this client.
+    fn signer_account(&self) -> ApiResult<AccountId32> {
+        self.api
+            .signer_account()
+            .ok_or_else(|| Error::InvalidInput("extrinsic requires a signer".into()))
+    }
+
+    /// Submit one of the transfer calls of the balances pallet.
+    fn balance_transfer(
+        &self,
+        call: &str,
+        to: &AccountId32,
+        amount: Tft,
+    ) -> ApiResult<Included<BalanceEvent>> {
+        if amount == Tft::ZERO {
+            return Err(Error::InvalidInput("can't transfer 0 TFT".into()));
+        }
+        let from = self.signer_account()?;
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "Balances",
+            call,
+            GenericAddress::Id(to.clone()),
+            Compact(amount.units())
+        );
+        self.submit_and_find(call, |e| match e {
+            TfchainEvent::Balance(BalanceEvent::Transfer(f, t, a))
+                if f == from && &t == to && a == amount =>
+            {
+                Some(BalanceEvent::Transfer(f, t, a))
+            }
+            _ => None,
+        })
+    }
+
+    /// Submit a single batch of consumption reports.
+    fn add_reports_batch(&self, batch: &[Consumption]) -> ApiResult<Included<Vec<Consumption>>> {
+        let reports: Vec<pallet_smart_contract::types::Consumption> =
+            batch.iter().cloned().map(Into::into).collect();
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "SmartContractModule",
+            "add_reports",
+            reports
+        );
+        let included = self.submit(call)?;
+        let accepted = included
+            .event
+            .into_iter()
+            .filter_map(smart_contract_event)
+            .filter_map(|e| match e {
+                SmartContractEvent::ConsumptionReportReceived(c) if batch.contains(&c) => Some(c),
+                _ => None,
+            })
+            .collect();
+        Ok(Included {
+            block: included.block,
+            event: accepted,
+        })
+    }
+
+    /// Submit a single batch of NRU consumption reports.
+    fn add_nru_reports_batch(
+        &self,
+        batch: &[NruConsumption],
+    ) -> ApiResult<Included<Vec<NruConsumption>>> {
+        let reports: Vec<pallet_smart_contract::types::NruConsumption> =
+            batch.iter().cloned().map(Into::into).collect();
+        let call = compose_call!(
+            self.api.metadata.clone(),
+            "SmartContractModule",
+            "add_nru_reports",
+            reports
+        );
+        let included = self.submit(call)?;
+        let accepted = included
+            .event
+            .into_iter()
+            .filter_map(smart_contract_event)
+            .filter_map(|e| match e {
+                SmartContractEvent::NruConsumption(contract_id, timestamp, window, nru) => {
+                    Some(NruConsumption {
+                        contract_id,
+                        timestamp,
+                        window,
+                        nru,
+                    })
+                }
+                _ => None,
+            })
+            .filter(|c| batch.contains(c))
+            .collect();
+        Ok(Included {
+            block: included.block,
+            event: accepted,
+        })
+    }
+
+    /// Sign a call with the next nonce of the signer, as handed out by its [NonceManager].
+    fn sign<C>(&self, call: C, nonces: &NonceManager) -> ApiResult<UncheckedExtrinsicV4<C>>
+    where
+        C: Encode + Clone,
+    {
+        let signer = self
+            .api
+            .signer
+            .clone()
+            .ok_or_else(|| Error::InvalidInput("extrinsic requires a signer".into()))?;
+        let nonce = nonces.reserve(|| self.account_next_index())?;
+        Ok(compose_extrinsic_offline!(
+            signer,
+            call,
+            nonce,
+            Era::Immortal,
+            self.api.genesis_hash,
+            self.api.genesis_hash,
+            self.api.runtime_version.spec_version,
+            self.api.runtime_version.transaction_version
+        ))
+    }
+
+    /// Submit a signed extrinsic, and wait until it reaches the given status.
+    fn send<C>(&self, xt: &UncheckedExtrinsicV4<C>, status: XtStatus) -> ApiResult<Option<Hash>>
+    where
+        C: Encode + Clone,
+    {
+        Ok(self.api.send_extrinsic(xt.hex_encode(), status)?)
+    }
+
+    /// Wait until the block with the given height and hash is finalized. Fails if a different
+    /// block is finalized at that height.
+    fn wait_finalized(&self, height: BlockNumber, block: Hash) -> ApiResult<()> {
+        while self.finalized_height()? < height {
+            thread::sleep(Duration::from_secs(BLOCK_TIME_SECONDS as u64));
+        }
+        match self.get_hash_at_height(height)? {
+            Some(hash) if hash == block => Ok(()),
+            _ => Err(Error::Dropped(format!(
+                "block {:?} including the extrinsic was retracted",
+                block
+            ))),
+        }
+    }
+
+    /// Find the index of an encoded extrinsic in a block.
+    fn extrinsic_index(&self, block: Hash, encoded: &[u8]) -> ApiResult<u32> {
+        let opaque: OpaqueBlock = self
+            .api
+            .get_block(Some(block))?
+            .ok_or_else(|| Error::NotFound(format!("block {:?}", block)))?;
+        opaque
+            .extrinsics
+            .iter()
+            .position(|xt| xt.encode() == encoded)
+            .map(|idx| idx as u32)
+            .ok_or_else(|| Error::NotFound(format!("extrinsic in block {:?}", block)))
+    }
+
+    /// Indicates if the metadata of the connected runtime describes the given block, i.e. the
+    /// block was produced by the same runtime version. Names are only resolved from the metadata
+    /// if it does, as they might be different in another version. If the runtime version of the
+    /// block can't be fetched, the metadata is assumed not to apply.
+    fn metadata_applies(&self, block: Option<Hash>) -> bool {
+        self.spec_version(block)
+            .map_or(false, |v| v == self.api.runtime_version.spec_version)
+    }
+
+    /// Resolve the names of module errors in an event from the metadata of the runtime.
+    fn resolve_event(&self, event: TfchainEvent) -> TfchainEvent {
+        match event {
+            TfchainEvent::System(SystemEvent::ExtrinsicFailed(error, info)) => {
+                TfchainEvent::System(SystemEvent::ExtrinsicFailed(
+                    self.resolve_dispatch_error(error),
+                    info,
+                ))
+            }
+            event => event,
+        }
+    }
+
+    /// Resolve the pallet and error name of a [DispatchError::Module] from the metadata of the
+    /// runtime.
+    fn resolve_dispatch_error(&self, error: DispatchError) -> DispatchError {
+        match error {
+            DispatchError::Module(mut me) => {
+                if let Ok(module) = self.api.metadata.module_with_errors(me.pallet_index) {
+                    me.pallet = Some(module.name.clone());
+                    me.error = module.error(me.error_index).ok().map(|e| e.to_string());
+                }
+                DispatchError::Module(me)
+            }
+            error => error,
+        }
+    }
+
+    /// Get the next nonce of the signer from the chain. This includes extrinsics of the signer
+    /// which are still in the transaction pool.
+    fn account_next_index(&self) -> ApiResult<u32> {
+        let account = self.signer_account()?;
+        let req = json!({
+            "method": "system_accountNextIndex",
+            "params": [account.to_string()],
+            "jsonrpc": "2.0",
+            "id": "1",
+        });
+        let resp = self
+            .api
+            .get_request(req.to_string())?
+            .ok_or_else(|| Error::NotFound(format!("next index of account {}", account)))?;
+        serde_json::from_str(&resp)
+            .map_err(|e| Error::Decode(format!("invalid account next index {}: {}", resp, e)))
+    }
+
+    /// The id of the twin of the signer of this client.
+    fn signer_twin_id(&self) -> ApiResult<u32> {
+        let account = self.signer_account()?;
+        self.api
+            .get_storage_map("TfgridModule", "TwinIdByAccountID", &account, None)?
+            .ok_or_else(|| Error::InvalidInput(format!("account {} has no twin", account)))
+    }
+
+    /// Submit a call and wait until it is included in a block. Then find the event emitted by the
+    /// extrinsic in that block, using `f` to select and convert it. If no event matches, the
+    /// extrinsic is considered to have failed.
+    fn submit_and_find<C, T, F>(&self, call: C, f: F) -> ApiResult<Included<T>>
+    where
+        C: Encode + Clone,
+        F: Fn(TfchainEvent) -> Option<T>,
+    {
+        let Included { block, event } = self.submit(call)?;
+        let event = event
+            .into_iter()
+            .find_map(f)
+            .ok_or_else(|| not_found_in(block))?;
+        Ok(Included { block, event })
+    }
+
+    /// Submit a call and wait until it is included in a block. Returns all events emitted by the
+    /// extrinsic, or an error if the extrinsic failed.
+    fn submit<C>(&self, call: C) -> ApiResult<Included<Vec<TfchainEvent>>>
+    where
+        C: Encode + Clone,
+    {
+        let receipt = self.submit_and_watch(call, TxStatus::InBlock)?;
+        match receipt.outcome {
+            Outcome::Success => Ok(Included {
+                block: receipt.block,
+                event: receipt.events,
+            }),
+            Outcome::Failed(error) => Err(Error::ExtrinsicFailed(format!(
+                "{} in block {:?}",
+                error, receipt.block
+            ))),
+        }
+    }
+}
+
+impl<P, E> RawClient<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+    E: support::Parameter + sp_runtime::traits::Member + DecodeExtrinsic,
+    TfchainEvent: From<E>,
+{
+    /// Get the extrinsics of a block, decoded into [TfchainCall]s, together with the events each
+    /// of them emitted.
+    pub fn get_block_extrinsics(&self, block: Option<Hash>) -> ApiResult<Vec<Extrinsic>> {
+        let opaque: OpaqueBlock = self
+            .api
+            .get_block(block)?
+            .ok_or_else(|| Error::NotFound(format!("block {:?}", block)))?;
+        // Fetch the events by hash, so they belong to the same block if no block was given.
+        let hash = opaque.header.hash();
+
+        let decoded = opaque
+            .extrinsics
+            .iter()
+            .enumerate()
+            .map(|(index, xt)| {
+                E::decode_extrinsic(&xt.encode()).map_err(|e| {
+                    Error::RuntimeMismatch(format!(
+                        "could not decode extrinsic {} of block {:?}: {}",
+                        index, hash, e
+                    ))
+                })
+            })
+            .collect::<ApiResult<Vec<_>>>()?;
+        let resolve = decoded.iter().any(|(_, call)| has_call_names(call))
+            && self.metadata_applies(Some(hash));
+        let mut extrinsics: Vec<_> = decoded
+            .into_iter()
+            .enumerate()
+            .map(|(index, (signer, call))| Extrinsic {
+                index: index as u32,
+                signer,
+                call: if resolve {
+                    self.resolve_call(call)
+                } else {
+                    call
+                },
+                events: Vec::new(),
+            })
+            .collect();
+        for record in self.get_block_events(Some(hash))? {
+            if let Phase::ApplyExtrinsic(idx) = record.phase {
+                if let Some(xt) = extrinsics.get_mut(idx as usize) {
+                    xt.events.push(record.event);
+                }
+            }
+        }
+
+        Ok(extrinsics)
+    }
+
+    /// Resolve the pallet and call name of a [TfchainCall::Other] from the metadata of the
+    /// runtime, including calls nested in a DAO proposal.
+    fn resolve_call(&self, call: TfchainCall) -> TfchainCall {
+        match call {
+            TfchainCall::Other {
+                pallet_index,
+                call_index,
+                ..
+            } => {
+                let module = self
+                    .api
+                    .metadata
+                    .modules_with_calls
+                    .values()
+                    .find(|m| m.index == pallet_index);
+                TfchainCall::Other {
+                    pallet_index,
+                    call_index,
+                    pallet: module.map(|m| m.name.clone()),
+                    call: module
+                        .and_then(|m| m.calls.iter().find(|(_, idx)| **idx == call_index))
+                        .map(|(name, _)| name.clone()),
+                }
+            }
+            TfchainCall::Dao(DaoCall::Propose {
+                threshold,
+                action,
+                description,
+                link,
+            }) => TfchainCall::Dao(DaoCall::Propose {
+                threshold,
+                action: Box::new(self.resolve_call(*action)),
+                description,
+                link,
+            }),
+            call => call,
+        }
+    }
+}
+
+/// An extrinsic which was included in a block.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Included<T> {
+    /// The hash of the block which included the extrinsic.
+    pub block: Hash,
+    /// The event emitted by the extrinsic.
+    pub event: T,
+}
+
+/// The result of submitting reports in batches, with the accepted reports of every batch.
+pub type BatchResult<T> = Result<Vec<Included<Vec<T>>>, BatchError<T>>;
+
+/// The error returned if submitting reports in batches failed part way.
+#[derive(Debug)]
+pub struct BatchError<T> {
+    /// The batches which were included before the failure, with the reports accepted in each.
+    pub included: Vec<Included<Vec<T>>>,
+    /// The error of the batch which failed. Batches after it were not submitted.
+    pub error: Error,
+}
+
+impl<T> fmt::Display for BatchError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "batch {} failed: {}",
+            self.included.len() + 1,
+            self.error
+        )
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for BatchError<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<T> From<Error> for BatchError<T> {
+    fn from(error: Error) -> Self {
+        BatchError {
+            included: Vec::new(),
+            error,
+        }
+    }
+}
+
+/// A node contract created by [RawClient::create_node_contract].
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeContractCreated {
+    /// The new contract.
+    pub contract: Contract,
+    /// The public IPs reserved for the contract, if any were requested.
+    pub reserved_ips: Vec<PublicIP>,
+}
+
+/// The error returned if the event expected from an extrinsic is not present in the block which
+/// included it.
+fn not_found_in(block: Hash) -> Error {
+    Error::ExtrinsicFailed(format!(
+        "expected event not found in block {:?}, the extrinsic likely failed",
+        block
+    ))
+}
+
+/// Select the [SmartContractEvent::ContractCreated] event of a contract of the given twin, for
+/// which `f` returns true.
+fn contract_created<'a, F>(twin_id: u32, f: F) -> impl Fn(TfchainEvent) -> Option<Contract> + 'a
+where
+    F: Fn(&ContractData) -> bool + 'a,
+{
+    move |e| match smart_contract_event(e)? {
+        SmartContractEvent::ContractCreated(c) if c.twin_id == twin_id && f(&c.contract_type) => {
+            Some(c)
+        }
+        _ => None,
+    }
+}
+
+/// Extract a [SmartContractEvent] from a [TfchainEvent].
+fn smart_contract_event(e: TfchainEvent) -> Option<SmartContractEvent> {
+    match e {
+        TfchainEvent::SmartContract(sce) => Some(sce),
+        _ => None,
+    }
+}
+
+/// Extract a [TFGridEvent] from a [TfchainEvent].
+fn tfgrid_event(e: TfchainEvent) -> Option<TFGridEvent> {
+    match e {
+        TfchainEvent::TFGrid(tfge) => Some(*tfge),
+        _ => None,
+    }
+}
+
+/// Validate the timestamps of reports, and submit them with `submit` in batches of at most
+/// [MAX_REPORTS_PER_EXTRINSIC]. Submission stops at the first batch which fails.
+fn submit_batches<T, K, F>(reports: &[T], key: K, submit: F) -> BatchResult<T>
+where
+    K: Fn(&T) -> (u64, u64),
+    F: Fn(&[T]) -> ApiResult<Included<Vec<T>>>,
+{
+    validation::monotonic_timestamps(reports, key)?;
+    let mut included = Vec::new();
+    for batch in reports.chunks(MAX_REPORTS_PER_EXTRINSIC) {
+        match submit(batch) {
+            Ok(batch) => included.push(batch),
+            Err(error) => return Err(BatchError { included, error }),
+        }
+    }
+    Ok(included)
+}
+
+/// Indicates if a call contains names which are resolved from the metadata.
+fn has_call_names(call: &TfchainCall) -> bool {
+    match call {
+        TfchainCall::Other { .. } => true,
+        TfchainCall::Dao(DaoCall::Propose { action, .. }) => has_call_names(action),
+        _ => false,
+    }
+}
+
+/// Indicates if an event contains names which are resolved from the metadata.
+fn has_error_names(event: &TfchainEvent) -> bool {
+    matches!(
+        event,
+        TfchainEvent::System(SystemEvent::ExtrinsicFailed(DispatchError::Module(_), _))
+    )
+}
+
+/// Select the [TFGridEvent::FarmUpdated] event of the given farm.
+fn farm_updated(farm_id: u32) -> impl Fn(TfchainEvent) -> Option<Farm> {
+    move |e| match tfgrid_event(e)? {
+        TFGridEvent::FarmUpdated(farm) if farm.id == farm_id => Some(farm),
+        _ => None,
+    }
+}
+
+/// Cache of the runtime spec versions at resolved block heights. Spec versions only ever increase,
+/// so if 2 resolved heights have the same spec version, all blocks in between have it as well.
+#[derive(Default)]
+struct SpecVersionCache {
+    resolved: BTreeMap<BlockNumber, u32>,
+}
+
+impl SpecVersionCache {
+    fn get(&self, height: BlockNumber) -> Option<u32> {
+        let (_, below) = self.resolved.range(..=height).next_back()?;
+        let (_, above) = self.resolved.range(height..).next()?;
+        if below == above {
+            Some(*below)
+        } else {
+            None
+        }
+    }
+
+    fn insert(&mut self, height: BlockNumber, spec_version: u32) {
+        self.resolved.insert(height, spec_version);
+        // Only the outer heights of a run with the same spec version are needed, so drop the
+        // neighbours which are now in the middle of such a run.
+        let below: Vec<_> = self.resolved.range(..height).rev().take(2).collect();
+        let above: Vec<_> = self.resolved.range(height + 1..).take(2).collect();
+        let mut redundant = Vec::new();
+        for neighbours in [below, above] {
+            if let [(h, v1), (_, v2)] = neighbours[..] {
+                if *v1 == spec_version && *v2 == spec_version {
+                    redundant.push(*h);
+                }
+            }
+        }
+        for h in redundant {
+            self.resolved.remove(&h);
+        }
+    }
+}
+
+/// Parse a hex encoded hash, with or without `0x` prefix.
+fn parse_hash(hash: &str) -> ApiResult<Hash> {
+    let hex_str = hash.strip_prefix("0x").unwrap_or(hash);
+    let mut raw_hash = [0; 32];
+    hex::decode_to_slice(hex_str, &mut raw_hash)
+        .map_err(|e| Error::InvalidInput(format!("invalid block hash {}: {}", hash, e)))?;
+    Ok(Hash::from(raw_hash))
+}
+
+/// Turn a failure to decode a storage value into an [Error::RuntimeMismatch]. Any other error,
+/// like a lost connection, is converted as usual so it can still be retried.
+fn runtime_mismatch(err: ApiClientError, what: &str) -> Error {
+    match err {
+        ApiClientError::StorageValueDecode(e) => {
+            Error::RuntimeMismatch(format!("could not decode {}: {}", what, e))
+        }
+        err => err.into(),
+    }
+}
+
+/// Encode a storage key as a hex string, as expected by the rpc calls.
+fn hex_key(key: &StorageKey) -> String {
+    format!("0x{}", hex::encode(&key.0))
+}
+
+/// Decode hex encoded storage data, as returned by the rpc calls.
+fn parse_hex(data: &str) -> ApiResult<Vec<u8>> {
+    hex::decode(data.strip_prefix("0x").unwrap_or(data))
+        .map_err(|e| Error::Decode(format!("invalid hex data {}: {}", data, e)))
+}
+
+/// Get the id a value of a storage map is stored under from its key. The maps which are listed
+/// use a hasher which appends the encoded id to its hash, so the key ends with the id.
+fn key_id<K: Decode>(key: &StorageKey) -> ApiResult<K> {
+    let id = key
+        .0
+        .len()
+        .checked_sub(std::mem::size_of::<K>())
+        .map(|start| &key.0[start..])
+        .ok_or_else(|| Error::Decode(format!("storage key too short: {}", hex_key(key))))?;
+    decode_value(id, "storage key id")
+}
+
+/// Decode a value read from chain storage.
+fn decode_value<V: Decode>(value: &[u8], what: &str) -> ApiResult<V> {
+    V::decode(&mut &value[..]).map_err(|e| Error::Decode(format!("invalid {}: {}", what, e)))
+}
+
+/// Connect to every endpoint except the active one, and get their finalized height.
+fn probe_endpoints<P>(urls: &[String], active: usize, signer: Option<P>) -> Probes<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    let results = urls
+        .iter()
+        .enumerate()
+        .map(|(idx, url)| {
+            if idx == active {
+                return (None, None);
+            }
+            match connect(url, signer.clone()) {
+                Ok(api) => {
+                    let height = finalized_height(&api).ok();
+                    (Some(api), height)
+                }
+                Err(_) => (None, None),
+            }
+        })
+        .collect();
+    Probes { active, results }
+}
+
+/// Get the height of the latest finalized block on the chain the given [Api] is connected to.
+fn finalized_height<P>(api: &Api<P>) -> ApiResult<BlockNumber>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    let hash = api
+        .get_finalized_head()?
+        .ok_or_else(|| Error::NotFound("finalized head".into()))?;
+    let header: runtime::Header = api
+        .get_header(Some(hash))?
+        .ok_or_else(|| Error::NotFound(format!("header of block {:?}", hash)))?;
+    Ok(header.number)
+}
+
+/// Set up a new connection to the chain.
+fn connect<P>(url: &str, signer: Option<P>) -> ApiResult<Api<P>>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    let api = Api::new(url.to_string()).map_err(Error::Transport)?;
+    Ok(match signer {
+        Some(signer) => api.set_signer(signer),
+        None => api,
+    })
 }
 
 /// A subscription on finalized heads. This iterator will never finish naturally. If it does it
-/// indicates a receiving error, and the client should create a new subscription.
+/// indicates a receiving error, or a head which could not be decoded, and the client should
+/// create a new subscription. Not every finalized block is guaranteed to be reported as a head,
+/// see [FinalizedBlocks] to follow every block.
 pub struct FinalizedHeadSubscription {
     stream: mpsc::Receiver<String>,
 }
@@ -619,6 +2513,201 @@ impl Iterator for FinalizedHeadSubscription {
 
     fn next(&mut self) -> Option<Self::Item> {
         let header_str = self.stream.recv().ok()?;
-        Some(serde_json::from_str(&header_str).unwrap())
+        serde_json::from_str(&header_str).ok()
+    }
+}
+
+/// A subscription on finalized blocks, which returns the height and hash of every finalized block
+/// exactly once, in order. If the underlying [FinalizedHeadSubscription] breaks, a new one is
+/// created. Blocks which are not reported as a head, either because they were finalized together
+/// with a later block or while resubscribing, are fetched by height.
+///
+/// Errors are returned as they occur. Polling the iterator again after an error continues at the
+/// same height, so it never finishes.
+pub struct FinalizedBlocks<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    client: SharedClient<P, E>,
+    subscription: Option<FinalizedHeadSubscription>,
+    /// The height of the next block to return, None until the first head is received.
+    next: Option<BlockNumber>,
+    /// The height of the latest finalized block seen.
+    head: BlockNumber,
+}
+
+impl<P, E> FinalizedBlocks<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+    E: support::sp_runtime::traits::Member + support::Parameter,
+    TfchainEvent: From<E>,
+{
+    /// Fetch the hash of a finalized block which was not reported as a head.
+    fn fill(&mut self, height: BlockNumber) -> ApiResult<(BlockNumber, Hash)> {
+        let hash = self
+            .client
+            .get_hash_at_height(height)?
+            .ok_or_else(|| Error::NotFound(format!("hash of finalized block {}", height)))?;
+        self.next = Some(height + 1);
+        Ok((height, hash))
+    }
+}
+
+impl<P, E> Iterator for FinalizedBlocks<P, E>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+    E: support::sp_runtime::traits::Member + support::Parameter,
+    TfchainEvent: From<E>,
+{
+    type Item = ApiResult<(BlockNumber, Hash)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(next) = self.next {
+                if next <= self.head {
+                    return Some(self.fill(next));
+                }
+            }
+
+            if self.subscription.is_none() {
+                match self.client.finalized_block_headers() {
+                    Ok(subscription) => self.subscription = Some(subscription),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            match self.subscription.as_mut().and_then(Iterator::next) {
+                Some(header) => {
+                    let next = *self.next.get_or_insert(header.number);
+                    self.head = self.head.max(header.number);
+                    if header.number == next {
+                        self.next = Some(next + 1);
+                        return Some(Ok((header.number, header.hash())));
+                    }
+                    // Heads below `next` were already returned, heads above it leave a gap which
+                    // is filled by height.
+                }
+                None => {
+                    // Blocks finalized while the subscription was broken are filled by height,
+                    // the new subscription only reports heads finalized after it is created.
+                    self.subscription = None;
+                    match self.client.finalized_height() {
+                        Ok(height) => self.head = self.head.max(height),
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(resolved: &[(BlockNumber, u32)]) -> SpecVersionCache {
+        let mut cache = SpecVersionCache::default();
+        for (height, spec_version) in resolved {
+            cache.insert(*height, *spec_version);
+        }
+        cache
+    }
+
+    #[test]
+    fn spec_version_cache_is_empty() {
+        assert_eq!(SpecVersionCache::default().get(10), None);
+    }
+
+    #[test]
+    fn spec_version_cache_returns_resolved_heights() {
+        let cache = cache(&[(10, 1), (20, 2)]);
+        assert_eq!(cache.get(10), Some(1));
+        assert_eq!(cache.get(20), Some(2));
+    }
+
+    #[test]
+    fn spec_version_cache_fills_runs() {
+        let cache = cache(&[(10, 1), (20, 1)]);
+        assert_eq!(cache.get(15), Some(1));
+        assert_eq!(cache.get(9), None);
+        assert_eq!(cache.get(21), None);
+    }
+
+    #[test]
+    fn spec_version_cache_unknown_across_upgrades() {
+        let cache = cache(&[(10, 1), (20, 2)]);
+        assert_eq!(cache.get(15), None);
+    }
+
+    #[test]
+    fn spec_version_cache_drops_redundant_heights() {
+        let cache = cache(&[(10, 1), (20, 1), (30, 1), (40, 2)]);
+        assert_eq!(
+            cache.resolved.keys().copied().collect::<Vec<_>>(),
+            vec![10, 30, 40]
+        );
+        assert_eq!(cache.get(25), Some(1));
+        assert_eq!(cache.get(35), None);
+    }
+
+    #[test]
+    fn spec_version_cache_insert_in_between() {
+        let cache = cache(&[(10, 1), (30, 2), (20, 1)]);
+        assert_eq!(cache.get(15), Some(1));
+        assert_eq!(cache.get(25), None);
+    }
+
+    #[test]
+    fn ids_are_read_from_the_end_of_storage_keys() {
+        // A map prefix and hash, followed by the encoded id.
+        let mut key = vec![0xaa; 48];
+        key.extend(7u32.encode());
+        assert_eq!(key_id::<u32>(&StorageKey(key.clone())).unwrap(), 7);
+
+        key.truncate(48);
+        key.extend(300_000u64.encode());
+        assert_eq!(key_id::<u64>(&StorageKey(key)).unwrap(), 300_000);
+
+        assert!(key_id::<u64>(&StorageKey(vec![1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn failed_read_after_submission_does_not_resubmit() {
+        let node = crate::mock_node::MockNode::start(10);
+        let retry = RetryPolicy::default()
+            .with_max_attempts(3)
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(10));
+        let client = Client::<sp_core::sr25519::Pair, runtime::Event>::with_endpoints(
+            Endpoints::from(node.url()),
+            None,
+            retry,
+        )
+        .unwrap();
+        let submitted = AtomicUsize::new(0);
+        let read = AtomicUsize::new(0);
+
+        let res: ApiResult<Included<()>> = client.submit_then_read(
+            |_| {
+                submitted.fetch_add(1, Ordering::SeqCst);
+                Ok(Included {
+                    block: crate::mock_node::hash(5),
+                    event: 1u32,
+                })
+            },
+            |_, block| {
+                assert_eq!(block, crate::mock_node::hash(5));
+                read.fetch_add(1, Ordering::SeqCst);
+                Err(Error::Transport(ApiClientError::Genesis))
+            },
+        );
+
+        assert!(matches!(
+            res,
+            Err(Error::Transport(ApiClientError::Genesis))
+        ));
+        assert_eq!(submitted.load(Ordering::SeqCst), 1);
+        assert_eq!(read.load(Ordering::SeqCst), 3);
     }
 }
@@ -0,0 +1,326 @@
+//! Configuration of the rpc endpoints a [Client](crate::client::Client) connects to, and the
+//! failover between them.
+
+use crate::error::Error;
+use crate::types::BlockNumber;
+use std::time::Duration;
+
+/// The default amount of blocks an endpoint can lag behind the best known endpoint before the
+/// client switches away from it.
+const DEFAULT_MAX_LAG: BlockNumber = 5;
+
+/// The default interval between 2 health checks of the configured endpoints.
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A list of websocket urls of tfchain nodes, which are all considered equivalent. The client
+/// connects to the first one, and switches to another endpoint if the active one disconnects, or
+/// falls behind the others.
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    urls: Vec<String>,
+    max_lag: BlockNumber,
+    health_check_interval: Duration,
+}
+
+impl Endpoints {
+    /// Create a new set of endpoints. The order of the urls is the order of preference. Returns
+    /// [Error::InvalidInput] if `urls` is empty.
+    pub fn new(urls: Vec<String>) -> Result<Self, Error> {
+        if urls.is_empty() {
+            return Err(Error::InvalidInput(
+                "at least 1 endpoint is required".into(),
+            ));
+        }
+        Ok(Endpoints {
+            urls,
+            max_lag: DEFAULT_MAX_LAG,
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+        })
+    }
+
+    /// Set the amount of blocks the finalized head of the active endpoint can be behind the best
+    /// endpoint, before switching to that one.
+    pub fn with_max_lag(mut self, max_lag: BlockNumber) -> Self {
+        self.max_lag = max_lag;
+        self
+    }
+
+    /// Set the interval at which all endpoints are checked. Checks are only started while the
+    /// client is in use, and run in the background. Health checks are never done if only a
+    /// single endpoint is configured.
+    pub fn with_health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// The configured endpoint urls.
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    pub(crate) fn max_lag(&self) -> BlockNumber {
+        self.max_lag
+    }
+
+    pub(crate) fn health_check_interval(&self) -> Duration {
+        self.health_check_interval
+    }
+
+    /// Pick the endpoint to use, given the finalized height of every endpoint as returned by a
+    /// health check, or [None] if it could not be reached. The active endpoint is kept as long
+    /// as it is reachable and does not lag more than the allowed amount of blocks behind the
+    /// best endpoint. Otherwise the most preferred endpoint at the best height is picked.
+    /// Returns [None] if no endpoint can be reached.
+    pub(crate) fn select(&self, heights: &[Option<BlockNumber>], active: usize) -> Option<usize> {
+        let best = heights.iter().flatten().max()?;
+        if let Some(Some(height)) = heights.get(active) {
+            if best - height <= self.max_lag {
+                return Some(active);
+            }
+        }
+        heights.iter().position(|h| h.as_ref() == Some(best))
+    }
+}
+
+impl From<String> for Endpoints {
+    fn from(url: String) -> Self {
+        Endpoints {
+            urls: vec![url],
+            max_lag: DEFAULT_MAX_LAG,
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+        }
+    }
+}
+
+impl TryFrom<Vec<String>> for Endpoints {
+    type Error = Error;
+
+    fn try_from(urls: Vec<String>) -> Result<Self, Self::Error> {
+        Endpoints::new(urls)
+    }
+}
+
+/// The result of a call, together with the endpoint which served it.
+#[derive(Debug, Clone)]
+pub struct Served<T> {
+    /// The value returned by the call.
+    pub value: T,
+    /// The url of the endpoint which served the call.
+    pub endpoint: String,
+}
+
+/// The state of a single endpoint, as observed during a health check.
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    /// The url of the endpoint.
+    pub url: String,
+    /// The height of the latest finalized block on the endpoint, or [None] if the endpoint could
+    /// not be reached.
+    pub finalized_height: Option<BlockNumber>,
+    /// Indicates if this is the endpoint currently used by the client.
+    pub active: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::mock_node::{self, MockNode};
+    use crate::retry::RetryPolicy;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    type TestClient = Client<sp_core::sr25519::Pair, runtime::Event>;
+
+    fn endpoints(amount: usize) -> Endpoints {
+        let urls = (0..amount).map(|i| format!("ws://node{}", i)).collect();
+        Endpoints::new(urls).unwrap().with_max_lag(5)
+    }
+
+    #[test]
+    fn empty_endpoints_are_rejected() {
+        assert!(matches!(
+            Endpoints::new(Vec::new()),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn select_keeps_active_within_lag() {
+        let endpoints = endpoints(3);
+        assert_eq!(
+            endpoints.select(&[Some(100), Some(105), Some(103)], 0),
+            Some(0)
+        );
+        assert_eq!(
+            endpoints.select(&[Some(100), Some(105), Some(103)], 2),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn select_switches_away_from_lagging_active() {
+        let endpoints = endpoints(3);
+        assert_eq!(
+            endpoints.select(&[Some(100), Some(106), Some(103)], 0),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn select_switches_away_from_unreachable_active() {
+        let endpoints = endpoints(3);
+        assert_eq!(endpoints.select(&[None, Some(100), Some(100)], 0), Some(1));
+        assert_eq!(endpoints.select(&[Some(100), None, Some(100)], 1), Some(0));
+    }
+
+    #[test]
+    fn select_prefers_first_endpoint_at_best_height() {
+        let endpoints = endpoints(3);
+        assert_eq!(endpoints.select(&[None, Some(110), Some(110)], 0), Some(1));
+        assert_eq!(
+            endpoints.select(&[Some(90), Some(100), Some(110)], 0),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn select_without_reachable_endpoints() {
+        let endpoints = endpoints(2);
+        assert_eq!(endpoints.select(&[None, None], 0), None);
+    }
+
+    /// Start a stand-in for a node, which accepts websocket connections and closes them right
+    /// away. Every accepted connection is reported with the given index.
+    fn closing_node(idx: usize, accepted: mpsc::Sender<usize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                // Report before closing, so the report is in before the client moves on.
+                if accepted.send(idx).is_err() {
+                    return;
+                }
+                drop(stream);
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn connect_tries_every_endpoint_in_order() {
+        let (accepted_in, accepted) = mpsc::channel();
+        let urls = vec![
+            closing_node(0, accepted_in.clone()),
+            closing_node(1, accepted_in),
+        ];
+
+        let res =
+            TestClient::with_endpoints(Endpoints::new(urls).unwrap(), None, RetryPolicy::never());
+
+        assert!(res.is_err());
+        assert_eq!(accepted.try_iter().collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn connect_reports_unreachable_endpoints() {
+        // Bind and drop a listener to get a local port nothing listens on.
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let res = TestClient::with_endpoints(
+            Endpoints::from(format!("ws://127.0.0.1:{}", port)),
+            None,
+            RetryPolicy::never(),
+        );
+        assert!(matches!(res, Err(Error::Transport(_))));
+    }
+
+    /// Connect to the given nodes, without background health checks, and with short delays
+    /// between retries.
+    fn connect_to(nodes: &[&MockNode]) -> TestClient {
+        let endpoints = Endpoints::new(nodes.iter().map(|n| n.url()).collect())
+            .unwrap()
+            .with_max_lag(5)
+            .with_health_check_interval(Duration::from_secs(3600));
+        let retry = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(10));
+        TestClient::with_endpoints(endpoints, None, retry).unwrap()
+    }
+
+    #[test]
+    fn failover_when_active_endpoint_disconnects() {
+        let first = MockNode::start(10);
+        let second = MockNode::start(10);
+        let client = connect_to(&[&first, &second]);
+        assert_eq!(client.active_endpoint(), first.url());
+
+        first.stop();
+        let served = client.served(|c| c.get_hash_at_height(3)).unwrap();
+
+        assert_eq!(served.value, Some(mock_node::hash(3)));
+        assert_eq!(served.endpoint, second.url());
+        assert_eq!(client.active_endpoint(), second.url());
+    }
+
+    #[test]
+    fn check_switches_away_from_lagging_endpoint() {
+        let lagging = MockNode::start(100);
+        let synced = MockNode::start(100);
+        let client = connect_to(&[&lagging, &synced]);
+        synced.set_finalized(200);
+
+        let status = client.check_endpoints();
+
+        assert_eq!(client.active_endpoint(), synced.url());
+        assert_eq!(client.finalized_height().unwrap(), 200);
+        let heights: Vec<_> = status.iter().map(|s| s.finalized_height).collect();
+        assert_eq!(heights, vec![Some(100), Some(200)]);
+        let active: Vec<_> = status.iter().map(|s| s.active).collect();
+        assert_eq!(active, vec![false, true]);
+    }
+
+    #[test]
+    fn check_keeps_endpoint_within_lag() {
+        let first = MockNode::start(100);
+        let second = MockNode::start(105);
+        let client = connect_to(&[&first, &second]);
+
+        client.check_endpoints();
+
+        assert_eq!(client.active_endpoint(), first.url());
+    }
+
+    #[test]
+    fn switching_does_not_wait_for_calls_in_progress() {
+        let lagging = MockNode::start(100);
+        let synced = MockNode::start(100);
+        let client = connect_to(&[&lagging, &synced]);
+        synced.set_finalized(200);
+
+        let (entered_in, entered) = mpsc::channel();
+        let (release, released) = mpsc::channel::<()>();
+        let client = &client;
+        thread::scope(|s| {
+            let call = s.spawn(move || {
+                client.served(|c| {
+                    entered_in.send(()).unwrap();
+                    released.recv().unwrap();
+                    c.finalized_height()
+                })
+            });
+            entered.recv().unwrap();
+            // The call is still running on the lagging endpoint while the client switches.
+            client.check_endpoints();
+            assert_eq!(client.active_endpoint(), synced.url());
+            release.send(()).unwrap();
+
+            let served = call.join().unwrap().unwrap();
+            assert_eq!(served.value, 100);
+            assert_eq!(served.endpoint, lagging.url());
+        });
+    }
+}
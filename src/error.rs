@@ -0,0 +1,90 @@
+//! The error type returned by all fallible operations in this crate.
+
+use crate::types::Hash;
+use std::fmt;
+use substrate_api_client::ApiClientError;
+
+/// The [Result](std::result::Result) type used throughout this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An `Error` contains details about a failed interaction with the chain.
+#[derive(Debug)]
+pub enum Error {
+    /// An error while communicating with the node.
+    Transport(ApiClientError),
+    /// A value returned by the node could not be decoded.
+    Decode(String),
+    /// A value which should always be present in chain storage was not found.
+    NotFound(String),
+    /// The input passed by the caller is invalid.
+    InvalidInput(String),
+    /// A block was requested for a timestamp which is past the timestamp of the latest block.
+    FutureTimestamp {
+        /// The requested timestamp, in seconds.
+        timestamp: i64,
+        /// The timestamp of the latest block, in seconds.
+        latest: i64,
+    },
+    /// Data on chain could not be decoded with the runtime types known to this client.
+    RuntimeMismatch(String),
+    /// An extrinsic was included in a block, but did not have the expected result.
+    ExtrinsicFailed(String),
+    /// An extrinsic was dropped from the transaction pool or found to be invalid, or the block
+    /// which included it was retracted.
+    Dropped(String),
+    /// An extrinsic was submitted, but an error occurred before its outcome was known. The
+    /// extrinsic might still be applied, so check for it on chain before submitting it again.
+    StatusUnknown {
+        /// The hash of the submitted extrinsic.
+        extrinsic: Hash,
+        /// The error which occurred after submitting the extrinsic.
+        reason: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(ref apie) => write!(f, "transport error: {}", apie),
+            Error::Decode(ref msg) => write!(f, "could not decode value: {}", msg),
+            Error::NotFound(ref what) => write!(f, "{} not found in chain storage", what),
+            Error::InvalidInput(ref msg) => write!(f, "invalid input: {}", msg),
+            Error::FutureTimestamp { timestamp, latest } => write!(
+                f,
+                "can't fetch block for future timestamp {} vs latest {}",
+                timestamp, latest
+            ),
+            Error::RuntimeMismatch(ref msg) => write!(f, "runtime mismatch: {}", msg),
+            Error::ExtrinsicFailed(ref msg) => write!(f, "extrinsic failed: {}", msg),
+            Error::Dropped(ref msg) => write!(f, "extrinsic dropped: {}", msg),
+            Error::StatusUnknown {
+                extrinsic,
+                ref reason,
+            } => write!(
+                f,
+                "status of submitted extrinsic {:?} is unknown: {}",
+                extrinsic, reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(ref apie) => Some(apie),
+            _ => None,
+        }
+    }
+}
+
+impl From<ApiClientError> for Error {
+    fn from(ace: ApiClientError) -> Self {
+        match ace {
+            ApiClientError::StorageValueDecode(e) => Error::Decode(e.to_string()),
+            ApiClientError::InvalidHexString(e) => Error::Decode(e.to_string()),
+            ApiClientError::Deserializing(e) => Error::Decode(e.to_string()),
+            ace => Error::Transport(ace),
+        }
+    }
+}
@@ -41,7 +41,44 @@ pub use tft_bridge::Event as TftBridgeEvent;
 pub use tft_price::Event as TftPriceEvent;
 pub use validator_set::Event as ValidatorSetEvent;
 
+use crate::types::Hash;
+
+/// An event emitted in a block, together with the phase of the block in which it was emitted.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockEvent {
+    /// The phase of the block in which the event was emitted.
+    pub phase: Phase,
+    /// The event itself.
+    pub event: TfchainEvent,
+    /// The topics of the event, which can be used to look up events in a block.
+    pub topics: Vec<Hash>,
+}
+
+/// The phase of a block in which an event is emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    /// The event was emitted while applying the extrinsic with the given index in the block.
+    ApplyExtrinsic(u32),
+    /// The event was emitted during the finalization of the block.
+    Finalization,
+    /// The event was emitted during the initialization of the block.
+    Initialization,
+}
+
+impl From<system::Phase> for Phase {
+    fn from(p: system::Phase) -> Self {
+        match p {
+            system::Phase::ApplyExtrinsic(idx) => Phase::ApplyExtrinsic(idx),
+            system::Phase::Finalization => Phase::Finalization,
+            system::Phase::Initialization => Phase::Initialization,
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TfchainEvent {
     System(SystemEvent),
     Burning(BurningEvent),
@@ -90,3 +127,53 @@ impl From<runtime::Event> for TfchainEvent {
         }
     }
 }
+
+impl TfchainEvent {
+    /// The name of the pallet which emitted the event, e.g. `SmartContract` for a
+    /// [TfchainEvent::SmartContract].
+    pub fn pallet(&self) -> &'static str {
+        match self {
+            TfchainEvent::System(_) => "System",
+            TfchainEvent::Burning(_) => "Burning",
+            TfchainEvent::KVStore(_) => "KVStore",
+            TfchainEvent::RuntimeUpgrade(_) => "RuntimeUpgrade",
+            TfchainEvent::SmartContract(_) => "SmartContract",
+            TfchainEvent::TFGrid(_) => "TFGrid",
+            TfchainEvent::ValidatorSet(_) => "ValidatorSet",
+            TfchainEvent::Balance(_) => "Balance",
+            TfchainEvent::Grandpa(_) => "Grandpa",
+            TfchainEvent::Sudo(_) => "Sudo",
+            TfchainEvent::TftPriceEvent(_) => "TftPrice",
+            TfchainEvent::TftBridgeEvent(_) => "TftBridge",
+            TfchainEvent::Scheduler(_) => "Scheduler",
+            TfchainEvent::Collective(_) => "Collective",
+            TfchainEvent::Session(_) => "Session",
+            TfchainEvent::Dao(_) => "Dao",
+            TfchainEvent::Unknown => "Unknown",
+        }
+    }
+
+    /// The name of the event within its pallet, e.g. `ContractCreated` for a
+    /// [SmartContractEvent::ContractCreated].
+    pub fn variant(&self) -> &'static str {
+        match self {
+            TfchainEvent::System(e) => e.variant(),
+            TfchainEvent::Burning(e) => e.variant(),
+            TfchainEvent::KVStore(e) => e.variant(),
+            TfchainEvent::RuntimeUpgrade(e) => e.variant(),
+            TfchainEvent::SmartContract(e) => e.variant(),
+            TfchainEvent::TFGrid(e) => e.variant(),
+            TfchainEvent::ValidatorSet(e) => e.variant(),
+            TfchainEvent::Balance(e) => e.variant(),
+            TfchainEvent::Grandpa(e) => e.variant(),
+            TfchainEvent::Sudo(e) => e.variant(),
+            TfchainEvent::TftPriceEvent(e) => e.variant(),
+            TfchainEvent::TftBridgeEvent(e) => e.variant(),
+            TfchainEvent::Scheduler(e) => e.variant(),
+            TfchainEvent::Collective(e) => e.variant(),
+            TfchainEvent::Session(e) => e.variant(),
+            TfchainEvent::Dao(e) => e.variant(),
+            TfchainEvent::Unknown => "Unknown",
+        }
+    }
+}
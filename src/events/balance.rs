@@ -1,57 +1,65 @@
-use crate::types::{Balance, BalanceStatus};
+use crate::types::{BalanceStatus, Tft};
 use sp_core::crypto::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// An account was created with some free balance. \[account, free_balance\]
-    Endowed(AccountId32, Balance),
+    Endowed(AccountId32, Tft),
     /// An account was removed whose balance was non-zero but below ExistentialDeposit,
     /// resulting in an outright loss. \[account, balance\]
-    DustLost(AccountId32, Balance),
+    DustLost(AccountId32, Tft),
     /// Transfer succeeded. \[from, to, value\]
-    Transfer(AccountId32, AccountId32, Balance),
+    Transfer(AccountId32, AccountId32, Tft),
     /// A balance was set by root. \[who, free, reserved\]
-    BalanceSet(AccountId32, Balance, Balance),
+    BalanceSet(AccountId32, Tft, Tft),
     /// Some amount was deposited (e.g. for transaction fees). \[who, deposit\]
-    Deposit(AccountId32, Balance),
+    Deposit(AccountId32, Tft),
     /// Some balance was reserved (moved from free to reserved). \[who, value\]
-    Reserved(AccountId32, Balance),
+    Reserved(AccountId32, Tft),
     /// Some balance was unreserved (moved from reserved to free). \[who, value\]
-    Unreserved(AccountId32, Balance),
+    Unreserved(AccountId32, Tft),
     /// Some balance was moved from the reserve of the first account to the second account.
     /// Final argument indicates the destination balance type.
     /// \[from, to, balance, destination_status\]
-    ReserveRepatriated(AccountId32, AccountId32, Balance, BalanceStatus),
+    ReserveRepatriated(AccountId32, AccountId32, Tft, BalanceStatus),
     /// Unknown event
     Unknown,
 }
 
+impl Event {
+    /// The name of the event, e.g. `Endowed`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::Endowed(..) => "Endowed",
+            Event::DustLost(..) => "DustLost",
+            Event::Transfer(..) => "Transfer",
+            Event::BalanceSet(..) => "BalanceSet",
+            Event::Deposit(..) => "Deposit",
+            Event::Reserved(..) => "Reserved",
+            Event::Unreserved(..) => "Unreserved",
+            Event::ReserveRepatriated(..) => "ReserveRepatriated",
+            Event::Unknown => "Unknown",
+        }
+    }
+}
+
 impl From<pallet_balance::Event<runtime::Runtime>> for Event {
     fn from(be: pallet_balance::Event<runtime::Runtime>) -> Self {
         match be {
-            pallet_balance::Event::Endowed(acc, balance) => {
-                Event::Endowed(acc, (balance as u64).into())
-            }
-            pallet_balance::Event::DustLost(acc, balance) => {
-                Event::DustLost(acc, (balance as u64).into())
-            }
+            pallet_balance::Event::Endowed(acc, balance) => Event::Endowed(acc, balance.into()),
+            pallet_balance::Event::DustLost(acc, balance) => Event::DustLost(acc, balance.into()),
             pallet_balance::Event::Transfer(from, to, balance) => {
-                Event::Transfer(from, to, (balance as u64).into())
+                Event::Transfer(from, to, balance.into())
             }
             pallet_balance::Event::BalanceSet(acc, from, to) => {
-                Event::BalanceSet(acc, (from as u64).into(), (to as u64).into())
-            }
-            pallet_balance::Event::Deposit(acc, amount) => {
-                Event::Deposit(acc, (amount as u64).into())
-            }
-            pallet_balance::Event::Reserved(acc, amount) => {
-                Event::Reserved(acc, (amount as u64).into())
-            }
-            pallet_balance::Event::Unreserved(acc, amount) => {
-                Event::Unreserved(acc, (amount as u64).into())
+                Event::BalanceSet(acc, from.into(), to.into())
             }
+            pallet_balance::Event::Deposit(acc, amount) => Event::Deposit(acc, amount.into()),
+            pallet_balance::Event::Reserved(acc, amount) => Event::Reserved(acc, amount.into()),
+            pallet_balance::Event::Unreserved(acc, amount) => Event::Unreserved(acc, amount.into()),
             pallet_balance::Event::ReserveRepatriated(from, to, amount, dest_status) => {
-                Event::ReserveRepatriated(from, to, (amount as u64).into(), dest_status)
+                Event::ReserveRepatriated(from, to, amount.into(), dest_status)
             }
             _ => Event::Unknown,
         }
@@ -1,9 +1,24 @@
-use crate::types::{Balance, BlockNumber};
+use crate::types::{BlockNumber, Tft};
 use sp_core::crypto::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
-    TokensBurned(AccountId32, Balance, BlockNumber, Vec<u8>),
+    TokensBurned(
+        AccountId32,
+        Tft,
+        BlockNumber,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+}
+
+impl Event {
+    /// The name of the event, e.g. `TokensBurned`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::TokensBurned(..) => "TokensBurned",
+        }
+    }
 }
 
 impl From<pallet_burning::Event<runtime::Runtime>> for Event {
@@ -14,7 +29,7 @@ impl From<pallet_burning::Event<runtime::Runtime>> for Event {
                 amount,
                 block,
                 data,
-            ) => Event::TokensBurned(acc, (amount as u64).into(), block, data),
+            ) => Event::TokensBurned(acc, amount.into(), block, data),
         }
     }
 }
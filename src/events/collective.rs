@@ -3,6 +3,7 @@ pub type MemberCount = u32;
 pub type ProposalIndex = u32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// A motion (given hash) has been proposed (by given account) with a threshold (given
     /// `MemberCount`).
@@ -31,6 +32,21 @@ pub enum Event {
     Closed(Hash, MemberCount, MemberCount),
 }
 
+impl Event {
+    /// The name of the event, e.g. `Proposed`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::Proposed(..) => "Proposed",
+            Event::Voted(..) => "Voted",
+            Event::Approved(..) => "Approved",
+            Event::Disapproved(..) => "Disapproved",
+            Event::Executed(..) => "Executed",
+            Event::MemberExecuted(..) => "MemberExecuted",
+            Event::Closed(..) => "Closed",
+        }
+    }
+}
+
 impl From<pallet_collective::Event<runtime::Runtime, pallet_collective::Instance1>> for Event {
     fn from(ce: pallet_collective::Event<runtime::Runtime, pallet_collective::Instance1>) -> Self {
         match ce {
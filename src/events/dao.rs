@@ -1,6 +1,7 @@
 use crate::types::{AccountId32, Hash};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     Voted(AccountId32, Hash, bool, u32, u32),
     Proposed(AccountId32, u32, Hash, u32),
@@ -12,6 +13,22 @@ pub enum Event {
     CouncilMemberVeto(Hash, AccountId32),
 }
 
+impl Event {
+    /// The name of the event, e.g. `Voted`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::Voted(..) => "Voted",
+            Event::Proposed(..) => "Proposed",
+            Event::Approved(..) => "Approved",
+            Event::Disapproved(..) => "Disapproved",
+            Event::Executed(..) => "Executed",
+            Event::Closed(..) => "Closed",
+            Event::ClosedByCouncil(..) => "ClosedByCouncil",
+            Event::CouncilMemberVeto(..) => "CouncilMemberVeto",
+        }
+    }
+}
+
 impl From<pallet_dao::Event<runtime::Runtime>> for Event {
     fn from(pde: pallet_dao::Event<runtime::Runtime>) -> Self {
         match pde {
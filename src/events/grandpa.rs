@@ -1,6 +1,7 @@
 use crate::types::{AuthorityId, AuthorityList};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// New authority set has been applied. \[authority_set\]
     NewAuthorities(AuthorityList),
@@ -10,6 +11,17 @@ pub enum Event {
     Resumed,
 }
 
+impl Event {
+    /// The name of the event, e.g. `NewAuthorities`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::NewAuthorities(..) => "NewAuthorities",
+            Event::Paused => "Paused",
+            Event::Resumed => "Resumed",
+        }
+    }
+}
+
 impl From<pallet_grandpa::Event> for Event {
     fn from(ge: pallet_grandpa::Event) -> Self {
         match ge {
@@ -1,13 +1,37 @@
 use sp_core::crypto::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// A user has set their entry
-    EntrySet(AccountId32, Vec<u8>, Vec<u8>),
+    EntrySet(
+        AccountId32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
     /// A user has read their entry, leaving it in storage
-    EntryGot(AccountId32, Vec<u8>, Vec<u8>),
+    EntryGot(
+        AccountId32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
     /// A user has read their entry, removing it from storage
-    EntryTaken(AccountId32, Vec<u8>, Vec<u8>),
+    EntryTaken(
+        AccountId32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
+}
+
+impl Event {
+    /// The name of the event, e.g. `EntrySet`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::EntrySet(..) => "EntrySet",
+            Event::EntryGot(..) => "EntryGot",
+            Event::EntryTaken(..) => "EntryTaken",
+        }
+    }
 }
 
 impl From<pallet_kvstore::Event<runtime::Runtime>> for Event {
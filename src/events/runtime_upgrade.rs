@@ -1,9 +1,19 @@
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// An empty variant which should never be constructible.
     Empty,
 }
 
+impl Event {
+    /// The name of the event, e.g. `Empty`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::Empty => "Empty",
+        }
+    }
+}
+
 impl From<pallet_runtime_upgrade::Event> for Event {
     fn from(_: pallet_runtime_upgrade::Event) -> Self {
         Event::Empty
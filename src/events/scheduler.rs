@@ -1,6 +1,7 @@
 use crate::types::BlockNumber;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// Scheduled some task. \[when, index\]
     Scheduled(BlockNumber, u32),
@@ -11,6 +12,17 @@ pub enum Event {
     Dispatched,
 }
 
+impl Event {
+    /// The name of the event, e.g. `Scheduled`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::Scheduled(..) => "Scheduled",
+            Event::Canceled(..) => "Canceled",
+            Event::Dispatched => "Dispatched",
+        }
+    }
+}
+
 impl From<pallet_scheduler::Event<runtime::Runtime>> for Event {
     fn from(se: pallet_scheduler::Event<runtime::Runtime>) -> Self {
         match se {
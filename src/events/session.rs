@@ -1,12 +1,22 @@
 pub type SessionIndex = u32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// New session has happened. Note that the argument is the \[session_index\], not the block
     /// number as the type might suggest.
     NewSession(SessionIndex),
 }
 
+impl Event {
+    /// The name of the event, e.g. `NewSession`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::NewSession(..) => "NewSession",
+        }
+    }
+}
+
 impl From<pallet_session::Event> for Event {
     fn from(se: pallet_session::Event) -> Self {
         match se {
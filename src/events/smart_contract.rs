@@ -1,7 +1,8 @@
-use crate::types::{Balance, Consumption, Contract, ContractBill, PublicIP, Resources};
+use crate::types::{Consumption, Contract, ContractBill, PublicIP, Resources, Tft};
 use sp_core::crypto::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// A new contract is created
     ContractCreated(Contract),
@@ -14,7 +15,11 @@ pub enum Event {
     /// Public ips have been reserved \[contract id, \[public ips\]\]
     IPsReserved(u64, Vec<PublicIP>),
     /// Public ips have been released \[contract id, \[public ips\]\]
-    IPsFreed(u64, Vec<Vec<u8>>),
+    IPsFreed(
+        u64,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str_vec"))]
+        Vec<Vec<u8>>,
+    ),
     /// A contract has been deployed (used?)
     ContractDeployed(u64, AccountId32),
     /// Consumption report has been received
@@ -22,7 +27,7 @@ pub enum Event {
     /// Contract has been billed
     ContractBilled(ContractBill),
     /// Tokens burned for contract payment \[Contract id, amount\]
-    TokensBurned(u64, Balance),
+    TokensBurned(u64, Tft),
     /// Update the resources used by a contract \[Contract id, resources\]
     UpdatedUsedResources(u64, Resources),
     /// Nru consumption reported by a node for contract \[Contract id, timestamp, window duration,
@@ -36,6 +41,29 @@ pub enum Event {
     ContractGracePeriodEnded(u64, u32, u32),
 }
 
+impl Event {
+    /// The name of the event, e.g. `ContractCreated`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::ContractCreated(..) => "ContractCreated",
+            Event::ContractUpdated(..) => "ContractUpdated",
+            Event::NodeContractCanceled(..) => "NodeContractCanceled",
+            Event::NameContractCanceled(..) => "NameContractCanceled",
+            Event::IPsReserved(..) => "IPsReserved",
+            Event::IPsFreed(..) => "IPsFreed",
+            Event::ContractDeployed(..) => "ContractDeployed",
+            Event::ConsumptionReportReceived(..) => "ConsumptionReportReceived",
+            Event::ContractBilled(..) => "ContractBilled",
+            Event::TokensBurned(..) => "TokensBurned",
+            Event::UpdatedUsedResources(..) => "UpdatedUsedResources",
+            Event::NruConsumption(..) => "NruConsumption",
+            Event::RentContractCancelled(..) => "RentContractCancelled",
+            Event::ContractGracePeriodStarted(..) => "ContractGracePeriodStarted",
+            Event::ContractGracePeriodEnded(..) => "ContractGracePeriodEnded",
+        }
+    }
+}
+
 impl From<pallet_smart_contract::Event<runtime::Runtime>> for Event {
     fn from(sce: pallet_smart_contract::Event<runtime::Runtime>) -> Self {
         match sce {
@@ -70,7 +98,7 @@ impl From<pallet_smart_contract::Event<runtime::Runtime>> for Event {
                 Event::ContractBilled(ctb.into())
             }
             pallet_smart_contract::Event::<runtime::Runtime>::TokensBurned(contract_id, amount) => {
-                Event::TokensBurned(contract_id, (amount as u64).into())
+                Event::TokensBurned(contract_id, amount.into())
             }
             pallet_smart_contract::Event::<runtime::Runtime>::UpdatedUsedResources(resources) => {
                 Event::UpdatedUsedResources(resources.contract_id, resources.used.into())
@@ -1,6 +1,7 @@
 use crate::types::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// A sudo just took place. \[result\]
     //TODO: Sudid(DispatchResult),
@@ -12,6 +13,17 @@ pub enum Event {
     SudoAsDone,
 }
 
+impl Event {
+    /// The name of the event, e.g. `Sudid`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::Sudid => "Sudid",
+            Event::KeyChanged(..) => "KeyChanged",
+            Event::SudoAsDone => "SudoAsDone",
+        }
+    }
+}
+
 impl From<pallet_sudo::Event<runtime::Runtime>> for Event {
     fn from(se: pallet_sudo::Event<runtime::Runtime>) -> Self {
         match se {
@@ -1,13 +1,16 @@
+use crate::types::{DispatchError, DispatchInfo};
 use sp_core::crypto::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// An extrinsic completed successfully. \[info\]
-    ExtrinsicSuccess,
-    //TODO: ExtrinsicSuccess(DispatchInfo),
+    ExtrinsicSuccess(DispatchInfo),
     /// An extrinsic failed. \[error, info\]
-    ExtrinsicFailed,
-    //TODO: ExtrinsicFailed(DispatchError, DispatchInfo),
+    ///
+    /// The names of the pallet and error of a [DispatchError::Module] are only resolved if the
+    /// event is retrieved through a [Client](crate::client::Client).
+    ExtrinsicFailed(DispatchError, DispatchInfo),
     /// `:code` was updated.
     CodeUpdated,
     /// A new \[account\] was created.
@@ -18,13 +21,26 @@ pub enum Event {
     Unknown,
 }
 
+impl Event {
+    /// The name of the event, e.g. `ExtrinsicSuccess`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::ExtrinsicSuccess(..) => "ExtrinsicSuccess",
+            Event::ExtrinsicFailed(..) => "ExtrinsicFailed",
+            Event::CodeUpdated => "CodeUpdated",
+            Event::NewAccount(..) => "NewAccount",
+            Event::KilledAccount(..) => "KilledAccount",
+            Event::Unknown => "Unknown",
+        }
+    }
+}
+
 impl From<system::Event<runtime::Runtime>> for Event {
     fn from(fse: system::Event<runtime::Runtime>) -> Self {
         match fse {
-            system::Event::ExtrinsicSuccess(_) => Event::ExtrinsicSuccess,
-            system::Event::ExtrinsicFailed(_, _) => {
-                Event::ExtrinsicFailed
-                // Event::ExtrinsicSuccess(DispatchError, DispatchInfo)
+            system::Event::ExtrinsicSuccess(info) => Event::ExtrinsicSuccess(info.into()),
+            system::Event::ExtrinsicFailed(error, info) => {
+                Event::ExtrinsicFailed(error.into(), info.into())
             }
             system::Event::CodeUpdated => Event::CodeUpdated,
             system::Event::NewAccount(acc) => Event::NewAccount(acc),
@@ -4,6 +4,7 @@ use crate::types::{
 };
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     FarmStored(Farm),
     FarmUpdated(Farm),
@@ -22,14 +23,21 @@ pub enum Event {
     TwinStored(Twin),
     TwinUpdated(Twin),
 
-    TwinEntityStored(u32, u32, Vec<u8>),
+    TwinEntityStored(
+        u32,
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_hex"))] Vec<u8>,
+    ),
     TwinEntityRemoved(u32, u32),
     TwinDeleted(u32),
 
     PricingPolicyStored(PricingPolicy),
     CertificationCodeStored(CertificationCodes),
     FarmingPolicyStored(FarmingPolicy<u32>),
-    FarmPayoutV2AddressRegistered(u32, Vec<u8>),
+    FarmPayoutV2AddressRegistered(
+        u32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
 
     /// Farm has been marked as dedicated \[farm id\]
     FarmMarkedAsDedicated(u32),
@@ -43,6 +51,42 @@ pub enum Event {
     FarmCertificationSet(u32, FarmCertification),
 }
 
+impl Event {
+    /// The name of the event, e.g. `FarmStored`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::FarmStored(..) => "FarmStored",
+            Event::FarmUpdated(..) => "FarmUpdated",
+            Event::FarmDeleted(..) => "FarmDeleted",
+            Event::NodeStored(..) => "NodeStored",
+            Event::NodeUpdated(..) => "NodeUpdated",
+            Event::NodeDeleted(..) => "NodeDeleted",
+            Event::NodeUptimeReported(..) => "NodeUptimeReported",
+            Event::NodePublicConfigStored(..) => "NodePublicConfigStored",
+            Event::EntityStored(..) => "EntityStored",
+            Event::EntityUpdated(..) => "EntityUpdated",
+            Event::EntityDeleted(..) => "EntityDeleted",
+            Event::TwinStored(..) => "TwinStored",
+            Event::TwinUpdated(..) => "TwinUpdated",
+            Event::TwinEntityStored(..) => "TwinEntityStored",
+            Event::TwinEntityRemoved(..) => "TwinEntityRemoved",
+            Event::TwinDeleted(..) => "TwinDeleted",
+            Event::PricingPolicyStored(..) => "PricingPolicyStored",
+            Event::CertificationCodeStored(..) => "CertificationCodeStored",
+            Event::FarmingPolicyStored(..) => "FarmingPolicyStored",
+            Event::FarmPayoutV2AddressRegistered(..) => "FarmPayoutV2AddressRegistered",
+            Event::FarmMarkedAsDedicated(..) => "FarmMarkedAsDedicated",
+            Event::ConnectionPriceSet(..) => "ConnectionPriceSet",
+            Event::NodeCertificationSet(..) => "NodeCertificationSet",
+            Event::NodeCertifierAdded(..) => "NodeCertifierAdded",
+            Event::NodeCertifierRemoved(..) => "NodeCertifierRemoved",
+            Event::FarmingPolicyUpdated(..) => "FarmingPolicyUpdated",
+            Event::FarmingPolicySet(..) => "FarmingPolicySet",
+            Event::FarmCertificationSet(..) => "FarmCertificationSet",
+        }
+    }
+}
+
 impl From<pallet_tfgrid::Event<runtime::Runtime>> for Event {
     fn from(tfge: pallet_tfgrid::Event<runtime::Runtime>) -> Self {
         match tfge {
@@ -1,28 +1,87 @@
 use crate::types::{
-    AccountId32, Balance, BurnTransaction, MintTransaction, RefundTransaction, StellarSignature,
+    AccountId32, BurnTransaction, MintTransaction, RefundTransaction, StellarSignature, Tft,
 };
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     // Minting events
-    MintTransactionProposed(Vec<u8>, AccountId32, Balance),
-    MintTransactionVoted(Vec<u8>),
+    MintTransactionProposed(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        AccountId32,
+        Tft,
+    ),
+    MintTransactionVoted(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
     MintCompleted(MintTransaction),
     // not emitted?
-    MintTransactionExpired(Vec<u8>, Balance, AccountId32),
+    MintTransactionExpired(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+        AccountId32,
+    ),
     // Burn events
-    BurnTransactionCreated(u64, Vec<u8>, Balance),
-    BurnTransactionProposed(u64, Vec<u8>, Balance),
+    BurnTransactionCreated(
+        u64,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+    ),
+    BurnTransactionProposed(
+        u64,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+    ),
     BurnTransactionSignatureAdded(u64, StellarSignature),
     BurnTransactionReady(u64),
     BurnTransactionProcessed(BurnTransaction),
-    BurnTransactionExpired(u64, Vec<u8>, Balance),
+    BurnTransactionExpired(
+        u64,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+    ),
     // Refund events
-    RefundTransactionCreated(Vec<u8>, Vec<u8>, Balance),
-    RefundTransactionsignatureAdded(Vec<u8>, StellarSignature),
-    RefundTransactionReady(Vec<u8>),
+    RefundTransactionCreated(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+    ),
+    RefundTransactionsignatureAdded(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        StellarSignature,
+    ),
+    RefundTransactionReady(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+    ),
     RefundTransactionProcessed(RefundTransaction),
-    RefundTransactionExpired(Vec<u8>, Vec<u8>, Balance),
+    RefundTransactionExpired(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))] Vec<u8>,
+        Tft,
+    ),
+}
+
+impl Event {
+    /// The name of the event, e.g. `MintTransactionProposed`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::MintTransactionProposed(..) => "MintTransactionProposed",
+            Event::MintTransactionVoted(..) => "MintTransactionVoted",
+            Event::MintCompleted(..) => "MintCompleted",
+            Event::MintTransactionExpired(..) => "MintTransactionExpired",
+            Event::BurnTransactionCreated(..) => "BurnTransactionCreated",
+            Event::BurnTransactionProposed(..) => "BurnTransactionProposed",
+            Event::BurnTransactionSignatureAdded(..) => "BurnTransactionSignatureAdded",
+            Event::BurnTransactionReady(..) => "BurnTransactionReady",
+            Event::BurnTransactionProcessed(..) => "BurnTransactionProcessed",
+            Event::BurnTransactionExpired(..) => "BurnTransactionExpired",
+            Event::RefundTransactionCreated(..) => "RefundTransactionCreated",
+            Event::RefundTransactionsignatureAdded(..) => "RefundTransactionsignatureAdded",
+            Event::RefundTransactionReady(..) => "RefundTransactionReady",
+            Event::RefundTransactionProcessed(..) => "RefundTransactionProcessed",
+            Event::RefundTransactionExpired(..) => "RefundTransactionExpired",
+        }
+    }
 }
 
 impl From<pallet_tft_bridge::Event<runtime::Runtime>> for Event {
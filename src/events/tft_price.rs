@@ -1,6 +1,7 @@
 use crate::types::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// Price of tft has been stored \[price\].
     PriceStored(f64),
@@ -8,6 +9,16 @@ pub enum Event {
     OffchainWorkerExecuted(AccountId32),
 }
 
+impl Event {
+    /// The name of the event, e.g. `PriceStored`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::PriceStored(..) => "PriceStored",
+            Event::OffchainWorkerExecuted(..) => "OffchainWorkerExecuted",
+        }
+    }
+}
+
 impl From<pallet_tft_price::Event<runtime::Runtime>> for Event {
     fn from(tpe: pallet_tft_price::Event<runtime::Runtime>) -> Self {
         match tpe {
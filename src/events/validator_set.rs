@@ -1,6 +1,7 @@
 use sp_core::crypto::AccountId32;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     /// New validator added.
     ValidatorAdded(AccountId32),
@@ -10,6 +11,17 @@ pub enum Event {
     Unknown,
 }
 
+impl Event {
+    /// The name of the event, e.g. `ValidatorAdded`.
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Event::ValidatorAdded(..) => "ValidatorAdded",
+            Event::ValidatorRemoved(..) => "ValidatorRemoved",
+            Event::Unknown => "Unknown",
+        }
+    }
+}
+
 impl From<substrate_validator_set::Event<runtime::Runtime>> for Event {
     fn from(svse: substrate_validator_set::Event<runtime::Runtime>) -> Self {
         match svse {
@@ -0,0 +1,379 @@
+//! Selection of events by the pallet which emitted them, their name, and the twins, nodes, farms,
+//! contracts and accounts involved in them. See [EventFilter].
+
+use crate::events::{
+    BalanceEvent, BurningEvent, CollectiveEvent, DaoEvent, KVEvent, SmartContractEvent, SudoEvent,
+    SystemEvent, TFGridEvent, TfchainEvent, TftBridgeEvent, ValidatorSetEvent,
+};
+use crate::types::{Contract, ContractData};
+use sp_core::crypto::AccountId32;
+
+/// An `EventFilter` selects events, so consumers don't need to match on the nested
+/// [TfchainEvent] variants themselves.
+///
+/// Every criterion accepts multiple values, and matches if the event matches any of them. An
+/// event only matches the filter if it matches all criteria which are set. An empty filter
+/// matches every event.
+///
+/// ```ignore
+/// // All events of the smart contract pallet about contract 123.
+/// let filter = EventFilter::new()
+///     .with_pallet("SmartContract")
+///     .with_contract(123);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pallets: Vec<String>,
+    variants: Vec<String>,
+    twins: Vec<u32>,
+    nodes: Vec<u32>,
+    farms: Vec<u32>,
+    contracts: Vec<u64>,
+    accounts: Vec<AccountId32>,
+}
+
+impl EventFilter {
+    /// Create an [EventFilter] which matches every event.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match events emitted by the pallet with this name, see [TfchainEvent::pallet]. Names
+    /// are compared case insensitive.
+    pub fn with_pallet(mut self, pallet: &str) -> Self {
+        self.pallets.push(pallet.to_string());
+        self
+    }
+
+    /// Only match events with this name, see [TfchainEvent::variant]. Names are compared case
+    /// insensitive.
+    pub fn with_variant(mut self, variant: &str) -> Self {
+        self.variants.push(variant.to_string());
+        self
+    }
+
+    /// Only match events involving the twin with this id.
+    pub fn with_twin(mut self, twin_id: u32) -> Self {
+        self.twins.push(twin_id);
+        self
+    }
+
+    /// Only match events involving the node with this id.
+    pub fn with_node(mut self, node_id: u32) -> Self {
+        self.nodes.push(node_id);
+        self
+    }
+
+    /// Only match events involving the farm with this id.
+    pub fn with_farm(mut self, farm_id: u32) -> Self {
+        self.farms.push(farm_id);
+        self
+    }
+
+    /// Only match events involving the contract with this id.
+    pub fn with_contract(mut self, contract_id: u64) -> Self {
+        self.contracts.push(contract_id);
+        self
+    }
+
+    /// Only match events involving this account.
+    pub fn with_account(mut self, account: AccountId32) -> Self {
+        self.accounts.push(account);
+        self
+    }
+
+    /// Check if an event matches this filter.
+    pub fn matches(&self, event: &TfchainEvent) -> bool {
+        if !self.pallets.is_empty()
+            && !self
+                .pallets
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(event.pallet()))
+        {
+            return false;
+        }
+        if !self.variants.is_empty() {
+            let variant = event.variant();
+            if !self
+                .variants
+                .iter()
+                .any(|v| v.eq_ignore_ascii_case(variant))
+            {
+                return false;
+            }
+        }
+        if self.twins.is_empty()
+            && self.nodes.is_empty()
+            && self.farms.is_empty()
+            && self.contracts.is_empty()
+            && self.accounts.is_empty()
+        {
+            return true;
+        }
+
+        let involved = Involved::of(event);
+        any_of(&self.twins, &involved.twins)
+            && any_of(&self.nodes, &involved.nodes)
+            && any_of(&self.farms, &involved.farms)
+            && any_of(&self.contracts, &involved.contracts)
+            && any_of(&self.accounts, &involved.accounts)
+    }
+}
+
+/// Check if any of the wanted values is present, or if nothing is wanted at all.
+fn any_of<T: PartialEq>(wanted: &[T], present: &[T]) -> bool {
+    wanted.is_empty() || wanted.iter().any(|w| present.contains(w))
+}
+
+/// The ids of the objects and accounts involved in an event.
+#[derive(Default)]
+struct Involved {
+    twins: Vec<u32>,
+    nodes: Vec<u32>,
+    farms: Vec<u32>,
+    contracts: Vec<u64>,
+    accounts: Vec<AccountId32>,
+}
+
+impl Involved {
+    fn of(event: &TfchainEvent) -> Involved {
+        let mut i = Involved::default();
+        match event {
+            TfchainEvent::TFGrid(e) => i.tfgrid(e),
+            TfchainEvent::SmartContract(e) => i.smart_contract(e),
+            TfchainEvent::Balance(e) => match e {
+                BalanceEvent::Endowed(acc, _)
+                | BalanceEvent::DustLost(acc, _)
+                | BalanceEvent::BalanceSet(acc, _, _)
+                | BalanceEvent::Deposit(acc, _)
+                | BalanceEvent::Reserved(acc, _)
+                | BalanceEvent::Unreserved(acc, _) => i.accounts.push(acc.clone()),
+                BalanceEvent::Transfer(from, to, _)
+                | BalanceEvent::ReserveRepatriated(from, to, _, _) => {
+                    i.accounts.extend([from.clone(), to.clone()])
+                }
+                BalanceEvent::Unknown => {}
+            },
+            TfchainEvent::System(e) => match e {
+                SystemEvent::NewAccount(acc) | SystemEvent::KilledAccount(acc) => {
+                    i.accounts.push(acc.clone())
+                }
+                _ => {}
+            },
+            TfchainEvent::KVStore(e) => match e {
+                KVEvent::EntrySet(acc, _, _)
+                | KVEvent::EntryGot(acc, _, _)
+                | KVEvent::EntryTaken(acc, _, _) => i.accounts.push(acc.clone()),
+            },
+            TfchainEvent::Burning(BurningEvent::TokensBurned(acc, _, _, _)) => {
+                i.accounts.push(acc.clone())
+            }
+            TfchainEvent::ValidatorSet(e) => match e {
+                ValidatorSetEvent::ValidatorAdded(acc)
+                | ValidatorSetEvent::ValidatorRemoved(acc) => i.accounts.push(acc.clone()),
+                ValidatorSetEvent::Unknown => {}
+            },
+            TfchainEvent::Sudo(SudoEvent::KeyChanged(acc)) => i.accounts.push(acc.clone()),
+            TfchainEvent::TftBridgeEvent(e) => match e {
+                TftBridgeEvent::MintTransactionProposed(_, acc, _)
+                | TftBridgeEvent::MintTransactionExpired(_, _, acc) => i.accounts.push(acc.clone()),
+                TftBridgeEvent::MintCompleted(tx) => i.accounts.push(tx.target.clone()),
+                _ => {}
+            },
+            TfchainEvent::Collective(e) => match e {
+                CollectiveEvent::Proposed(acc, _, _, _)
+                | CollectiveEvent::Voted(acc, _, _, _, _) => i.accounts.push(acc.clone()),
+                _ => {}
+            },
+            TfchainEvent::Dao(e) => match e {
+                DaoEvent::Voted(acc, _, _, _, _)
+                | DaoEvent::Proposed(acc, _, _, _)
+                | DaoEvent::CouncilMemberVeto(_, acc) => i.accounts.push(acc.clone()),
+                DaoEvent::ClosedByCouncil(_, accs) => i.accounts.extend(accs.iter().cloned()),
+                _ => {}
+            },
+            _ => {}
+        }
+        i
+    }
+
+    fn tfgrid(&mut self, event: &TFGridEvent) {
+        match event {
+            TFGridEvent::FarmStored(farm) | TFGridEvent::FarmUpdated(farm) => {
+                self.farms.push(farm.id);
+                self.twins.push(farm.twin_id);
+            }
+            TFGridEvent::FarmDeleted(farm_id)
+            | TFGridEvent::FarmPayoutV2AddressRegistered(farm_id, _)
+            | TFGridEvent::FarmMarkedAsDedicated(farm_id)
+            | TFGridEvent::FarmingPolicySet(farm_id, _)
+            | TFGridEvent::FarmCertificationSet(farm_id, _) => self.farms.push(*farm_id),
+            TFGridEvent::NodeStored(node) | TFGridEvent::NodeUpdated(node) => {
+                self.nodes.push(node.id);
+                self.farms.push(node.farm_id);
+                self.twins.push(node.twin_id);
+            }
+            TFGridEvent::NodeDeleted(node_id)
+            | TFGridEvent::NodeUptimeReported(node_id, _, _)
+            | TFGridEvent::NodePublicConfigStored(node_id, _)
+            | TFGridEvent::NodeCertificationSet(node_id, _) => self.nodes.push(*node_id),
+            TFGridEvent::EntityStored(entity) | TFGridEvent::EntityUpdated(entity) => {
+                self.accounts.push(entity.account_id.clone())
+            }
+            TFGridEvent::TwinStored(twin) | TFGridEvent::TwinUpdated(twin) => {
+                self.twins.push(twin.id);
+                self.accounts.push(twin.account_id.clone());
+            }
+            TFGridEvent::TwinEntityStored(twin_id, _, _)
+            | TFGridEvent::TwinEntityRemoved(twin_id, _)
+            | TFGridEvent::TwinDeleted(twin_id) => self.twins.push(*twin_id),
+            TFGridEvent::NodeCertifierAdded(acc) | TFGridEvent::NodeCertifierRemoved(acc) => {
+                self.accounts.push(acc.clone())
+            }
+            _ => {}
+        }
+    }
+
+    fn smart_contract(&mut self, event: &SmartContractEvent) {
+        match event {
+            SmartContractEvent::ContractCreated(contract)
+            | SmartContractEvent::ContractUpdated(contract) => self.contract(contract),
+            SmartContractEvent::NodeContractCanceled(contract_id, node_id, twin_id)
+            | SmartContractEvent::ContractGracePeriodStarted(contract_id, node_id, twin_id, _)
+            | SmartContractEvent::ContractGracePeriodEnded(contract_id, node_id, twin_id) => {
+                self.contracts.push(*contract_id);
+                self.nodes.push(*node_id);
+                self.twins.push(*twin_id);
+            }
+            SmartContractEvent::ContractDeployed(contract_id, acc) => {
+                self.contracts.push(*contract_id);
+                self.accounts.push(acc.clone());
+            }
+            SmartContractEvent::NameContractCanceled(contract_id)
+            | SmartContractEvent::IPsReserved(contract_id, _)
+            | SmartContractEvent::IPsFreed(contract_id, _)
+            | SmartContractEvent::TokensBurned(contract_id, _)
+            | SmartContractEvent::UpdatedUsedResources(contract_id, _)
+            | SmartContractEvent::NruConsumption(contract_id, _, _, _)
+            | SmartContractEvent::RentContractCancelled(contract_id) => {
+                self.contracts.push(*contract_id)
+            }
+            SmartContractEvent::ConsumptionReportReceived(consumption) => {
+                self.contracts.push(consumption.contract_id)
+            }
+            SmartContractEvent::ContractBilled(bill) => self.contracts.push(bill.contract_id),
+        }
+    }
+
+    fn contract(&mut self, contract: &Contract) {
+        self.contracts.push(contract.contract_id);
+        self.twins.push(contract.twin_id);
+        match &contract.contract_type {
+            ContractData::NodeContract(nc) => self.nodes.push(nc.node_id),
+            ContractData::RentContract(rc) => self.nodes.push(rc.node_id),
+            ContractData::NameContract(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(seed: u8) -> AccountId32 {
+        AccountId32::new([seed; 32])
+    }
+
+    fn canceled(contract_id: u64, node_id: u32, twin_id: u32) -> TfchainEvent {
+        TfchainEvent::SmartContract(SmartContractEvent::NodeContractCanceled(
+            contract_id,
+            node_id,
+            twin_id,
+        ))
+    }
+
+    fn transfer(from: u8, to: u8) -> TfchainEvent {
+        TfchainEvent::Balance(BalanceEvent::Transfer(
+            account(from),
+            account(to),
+            1u128.into(),
+        ))
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = EventFilter::new();
+        assert!(filter.matches(&canceled(1, 2, 3)));
+        assert!(filter.matches(&transfer(1, 2)));
+        assert!(filter.matches(&TfchainEvent::Unknown));
+    }
+
+    #[test]
+    fn variant_is_the_name_of_the_event() {
+        assert_eq!(canceled(1, 2, 3).variant(), "NodeContractCanceled");
+        assert_eq!(transfer(1, 2).variant(), "Transfer");
+        assert_eq!(TfchainEvent::Unknown.variant(), "Unknown");
+    }
+
+    #[test]
+    fn pallet_and_variant_are_case_insensitive() {
+        let event = canceled(1, 2, 3);
+        assert!(EventFilter::new()
+            .with_pallet("smartcontract")
+            .matches(&event));
+        assert!(EventFilter::new()
+            .with_variant("NODECONTRACTCANCELED")
+            .matches(&event));
+        assert!(!EventFilter::new().with_pallet("TFGrid").matches(&event));
+        assert!(!EventFilter::new()
+            .with_variant("ContractCreated")
+            .matches(&event));
+    }
+
+    #[test]
+    fn values_of_one_criterion_are_alternatives() {
+        let filter = EventFilter::new().with_contract(5).with_contract(1);
+        assert!(filter.matches(&canceled(1, 2, 3)));
+        assert!(filter.matches(&canceled(5, 2, 3)));
+        assert!(!filter.matches(&canceled(7, 2, 3)));
+    }
+
+    #[test]
+    fn all_criteria_must_match() {
+        let filter = EventFilter::new()
+            .with_pallet("SmartContract")
+            .with_contract(1)
+            .with_node(2);
+        assert!(filter.matches(&canceled(1, 2, 3)));
+        assert!(!filter.matches(&canceled(1, 4, 3)));
+        assert!(!filter.matches(&canceled(4, 2, 3)));
+        assert!(!EventFilter::new()
+            .with_pallet("Balance")
+            .with_contract(1)
+            .matches(&canceled(1, 2, 3)));
+    }
+
+    #[test]
+    fn events_without_the_object_do_not_match() {
+        let filter = EventFilter::new().with_twin(3);
+        assert!(filter.matches(&canceled(1, 2, 3)));
+        assert!(!filter.matches(&transfer(1, 2)));
+        assert!(!filter.matches(&TfchainEvent::Unknown));
+    }
+
+    #[test]
+    fn accounts_match_either_side_of_a_transfer() {
+        let filter = EventFilter::new().with_account(account(2));
+        assert!(filter.matches(&transfer(1, 2)));
+        assert!(filter.matches(&transfer(2, 3)));
+        assert!(!filter.matches(&transfer(1, 3)));
+    }
+
+    #[test]
+    fn objects_nested_in_events_are_matched() {
+        let event = TfchainEvent::TFGrid(Box::new(TFGridEvent::FarmDeleted(9)));
+        assert!(EventFilter::new().with_farm(9).matches(&event));
+        assert!(!EventFilter::new().with_farm(8).matches(&event));
+        assert!(!EventFilter::new().with_node(9).matches(&event));
+    }
+}
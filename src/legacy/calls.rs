@@ -0,0 +1,38 @@
+//! Decoding of calls of the legacy runtime. Only the pallets which are shared with the current
+//! runtime are retyped, calls of other pallets are decoded as [TfchainCall::Other].
+
+use crate::calls::{signer, DecodeExtrinsic, Signer, TfchainCall};
+use codec::{Decode, Encode};
+
+mod balance;
+mod tft_bridge;
+
+pub use crate::calls::BalanceCall;
+pub use crate::calls::TftBridgeCall;
+
+impl From<runtime_legacy::Call> for TfchainCall {
+    fn from(c: runtime_legacy::Call) -> Self {
+        let other = TfchainCall::other(&c.encode());
+        let call = match c {
+            runtime_legacy::Call::Balances(c) => {
+                BalanceCall::try_from(c).map(TfchainCall::Balance).ok()
+            }
+            runtime_legacy::Call::TFTBridgeModule(c) => {
+                TftBridgeCall::try_from(c).map(TfchainCall::TftBridge).ok()
+            }
+            _ => None,
+        };
+        call.unwrap_or(other)
+    }
+}
+
+impl DecodeExtrinsic for runtime_legacy::Event {
+    fn decode_extrinsic(encoded: &[u8]) -> Result<(Option<Signer>, TfchainCall), codec::Error> {
+        let xt = runtime_legacy::UncheckedExtrinsic::decode(&mut &encoded[..])?;
+        let signer = xt
+            .signature
+            .map(|(address, _, extra)| signer(address, &extra))
+            .transpose()?;
+        Ok((signer, xt.function.into()))
+    }
+}
@@ -0,0 +1,24 @@
+use crate::calls::{lookup, BalanceCall as Call};
+
+impl TryFrom<pallet_balance::Call<runtime_legacy::Runtime>> for Call {
+    type Error = pallet_balance::Call<runtime_legacy::Runtime>;
+
+    fn try_from(bc: pallet_balance::Call<runtime_legacy::Runtime>) -> Result<Self, Self::Error> {
+        let call = match bc.clone() {
+            pallet_balance::Call::transfer(dest, value) => {
+                lookup(dest).map(|dest| Call::Transfer(dest, value.into()))
+            }
+            pallet_balance::Call::set_balance(who, free, reserved) => {
+                lookup(who).map(|who| Call::SetBalance(who, free.into(), reserved.into()))
+            }
+            pallet_balance::Call::force_transfer(source, dest, value) => lookup(source)
+                .zip(lookup(dest))
+                .map(|(source, dest)| Call::ForceTransfer(source, dest, value.into())),
+            pallet_balance::Call::transfer_keep_alive(dest, value) => {
+                lookup(dest).map(|dest| Call::TransferKeepAlive(dest, value.into()))
+            }
+            _ => None,
+        };
+        call.ok_or(bc)
+    }
+}
@@ -0,0 +1,66 @@
+use crate::calls::TftBridgeCall as Call;
+
+impl TryFrom<pallet_tft_bridge::Call<runtime_legacy::Runtime>> for Call {
+    type Error = pallet_tft_bridge::Call<runtime_legacy::Runtime>;
+
+    fn try_from(
+        tbc: pallet_tft_bridge::Call<runtime_legacy::Runtime>,
+    ) -> Result<Self, Self::Error> {
+        Ok(match tbc {
+            pallet_tft_bridge::Call::add_bridge_validator(target) => {
+                Call::AddBridgeValidator(target)
+            }
+            pallet_tft_bridge::Call::remove_bridge_validator(target) => {
+                Call::RemoveBridgeValidator(target)
+            }
+            pallet_tft_bridge::Call::set_fee_account(target) => Call::SetFeeAccount(target),
+            pallet_tft_bridge::Call::set_withdraw_fee(amount) => {
+                Call::SetWithdrawFee(amount.into())
+            }
+            pallet_tft_bridge::Call::set_deposit_fee(amount) => Call::SetDepositFee(amount.into()),
+            pallet_tft_bridge::Call::swap_to_stellar(target, amount) => {
+                Call::SwapToStellar(target, amount.into())
+            }
+            pallet_tft_bridge::Call::propose_or_vote_mint_transaction(tx_id, target, amount) => {
+                Call::ProposeOrVoteMintTransaction(tx_id, target, amount.into())
+            }
+            pallet_tft_bridge::Call::propose_burn_transaction_or_add_sig(
+                tx_id,
+                target,
+                amount,
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ) => Call::ProposeBurnTransactionOrAddSig(
+                tx_id,
+                target,
+                amount.into(),
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ),
+            pallet_tft_bridge::Call::set_burn_transaction_executed(tx_id) => {
+                Call::SetBurnTransactionExecuted(tx_id)
+            }
+            pallet_tft_bridge::Call::create_refund_transaction_or_add_sig(
+                tx_hash,
+                target,
+                amount,
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ) => Call::CreateRefundTransactionOrAddSig(
+                tx_hash,
+                target,
+                amount.into(),
+                signature,
+                stellar_pub_key,
+                sequence_number,
+            ),
+            pallet_tft_bridge::Call::set_refund_transaction_executed(tx_hash) => {
+                Call::SetRefundTransactionExecuted(tx_hash)
+            }
+            tbc => return Err(tbc),
+        })
+    }
+}
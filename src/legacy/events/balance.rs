@@ -4,28 +4,28 @@ impl From<pallet_balance::Event<runtime_legacy::Runtime>> for BalanceEvent {
     fn from(be: pallet_balance::Event<runtime_legacy::Runtime>) -> Self {
         match be {
             pallet_balance::Event::Endowed(acc, balance) => {
-                BalanceEvent::Endowed(acc, (balance as u64).into())
+                BalanceEvent::Endowed(acc, balance.into())
             }
             pallet_balance::Event::DustLost(acc, balance) => {
-                BalanceEvent::DustLost(acc, (balance as u64).into())
+                BalanceEvent::DustLost(acc, balance.into())
             }
             pallet_balance::Event::Transfer(from, to, balance) => {
-                BalanceEvent::Transfer(from, to, (balance as u64).into())
+                BalanceEvent::Transfer(from, to, balance.into())
             }
             pallet_balance::Event::BalanceSet(acc, from, to) => {
-                BalanceEvent::BalanceSet(acc, (from as u64).into(), (to as u64).into())
+                BalanceEvent::BalanceSet(acc, from.into(), to.into())
             }
             pallet_balance::Event::Deposit(acc, amount) => {
-                BalanceEvent::Deposit(acc, (amount as u64).into())
+                BalanceEvent::Deposit(acc, amount.into())
             }
             pallet_balance::Event::Reserved(acc, amount) => {
-                BalanceEvent::Reserved(acc, (amount as u64).into())
+                BalanceEvent::Reserved(acc, amount.into())
             }
             pallet_balance::Event::Unreserved(acc, amount) => {
-                BalanceEvent::Unreserved(acc, (amount as u64).into())
+                BalanceEvent::Unreserved(acc, amount.into())
             }
             pallet_balance::Event::ReserveRepatriated(from, to, amount, dest_status) => {
-                BalanceEvent::ReserveRepatriated(from, to, (amount as u64).into(), dest_status)
+                BalanceEvent::ReserveRepatriated(from, to, amount.into(), dest_status)
             }
             _ => BalanceEvent::Unknown,
         }
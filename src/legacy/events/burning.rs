@@ -8,7 +8,7 @@ impl From<pallet_burning_legacy::Event<runtime_legacy::Runtime>> for BurningEven
                 amount,
                 block,
                 data,
-            ) => BurningEvent::TokensBurned(acc, (amount as u64).into(), block, data),
+            ) => BurningEvent::TokensBurned(acc, amount.into(), block, data),
         }
     }
 }
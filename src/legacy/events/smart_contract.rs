@@ -34,7 +34,7 @@ impl From<pallet_smart_contract_legacy::Event<runtime_legacy::Runtime>> for Smar
                 SmartContractEvent::ContractBilled(ctb.into())
             }
             pallet_smart_contract_legacy::Event::<runtime_legacy::Runtime>::TokensBurned(contract_id, amount) => {
-                SmartContractEvent::TokensBurned(contract_id, (amount as u64).into())
+                SmartContractEvent::TokensBurned(contract_id, amount.into())
             }
             pallet_smart_contract_legacy::Event::<runtime_legacy::Runtime>::UpdatedUsedResources(resources) => {
                 SmartContractEvent::UpdatedUsedResources(resources.contract_id, resources.used.into())
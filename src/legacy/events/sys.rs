@@ -3,10 +3,9 @@ use crate::events::SystemEvent;
 impl From<system::Event<runtime_legacy::Runtime>> for SystemEvent {
     fn from(fse: system::Event<runtime_legacy::Runtime>) -> Self {
         match fse {
-            system::Event::ExtrinsicSuccess(_) => SystemEvent::ExtrinsicSuccess,
-            system::Event::ExtrinsicFailed(_, _) => {
-                SystemEvent::ExtrinsicFailed
-                // Event::ExtrinsicSuccess(DispatchError, DispatchInfo)
+            system::Event::ExtrinsicSuccess(info) => SystemEvent::ExtrinsicSuccess(info.into()),
+            system::Event::ExtrinsicFailed(error, info) => {
+                SystemEvent::ExtrinsicFailed(error.into(), info.into())
             }
             system::Event::CodeUpdated => SystemEvent::CodeUpdated,
             system::Event::NewAccount(acc) => SystemEvent::NewAccount(acc),
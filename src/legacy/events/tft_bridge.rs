@@ -1,28 +1,28 @@
 use crate::types::{
-    AccountId32, Balance, BurnTransaction, MintTransaction, RefundTransaction, StellarSignature,
+    AccountId32, BurnTransaction, MintTransaction, RefundTransaction, StellarSignature, Tft,
 };
 
 #[derive(Debug)]
 pub enum Event {
     // Minting events
-    MintTransactionProposed(Vec<u8>, AccountId32, Balance),
+    MintTransactionProposed(Vec<u8>, AccountId32, Tft),
     MintTransactionVoted(Vec<u8>),
     MintCompleted(MintTransaction),
     // not emitted?
-    MintTransactionExpired(Vec<u8>, Balance, AccountId32),
+    MintTransactionExpired(Vec<u8>, Tft, AccountId32),
     // Burn events
-    BurnTransactionCreated(u64, Vec<u8>, Balance),
-    BurnTransactionProposed(u64, Vec<u8>, Balance),
+    BurnTransactionCreated(u64, Vec<u8>, Tft),
+    BurnTransactionProposed(u64, Vec<u8>, Tft),
     BurnTransactionSignatureAdded(u64, StellarSignature),
     BurnTransactionReady(u64),
     BurnTransactionProcessed(BurnTransaction),
-    BurnTransactionExpired(u64, Vec<u8>, Balance),
+    BurnTransactionExpired(u64, Vec<u8>, Tft),
     // Refund events
-    RefundTransactionCreated(Vec<u8>, Vec<u8>, Balance),
+    RefundTransactionCreated(Vec<u8>, Vec<u8>, Tft),
     RefundTransactionsignatureAdded(Vec<u8>, StellarSignature),
     RefundTransactionReady(Vec<u8>),
     RefundTransactionProcessed(RefundTransaction),
-    RefundTransactionExpired(Vec<u8>, Vec<u8>, Balance),
+    RefundTransactionExpired(Vec<u8>, Vec<u8>, Tft),
 }
 
 impl From<pallet_tft_bridge::Event<runtime_legacy::Runtime>> for Event {
@@ -1,10 +1,32 @@
 pub use sp_core::crypto::AccountId32;
 pub use support;
 
+#[cfg(feature = "async")]
+pub mod async_client;
+#[cfg(feature = "async")]
+pub mod async_window;
+pub mod calls;
 pub mod client;
+pub mod endpoints;
+pub mod error;
 pub mod events;
+pub mod filter;
 pub mod legacy;
+#[cfg(test)]
+mod mock_node;
+mod nonce;
+pub mod receipt;
+pub mod retry;
 pub mod runtimes;
+pub mod scanner;
+#[cfg(feature = "serde")]
+pub mod serialization;
 pub mod types;
+pub mod validation;
 pub mod window;
+#[cfg(feature = "async")]
+pub use async_client::AsyncClient;
 pub use client::Client;
+pub use endpoints::Endpoints;
+pub use error::{Error, Result};
+pub use retry::RetryPolicy;
@@ -1,7 +1,25 @@
-use clap::{App, Arg};
+mod output;
+
+use clap::{App, Arg, ArgMatches};
 use git_version::git_version;
+use output::{Format, Output};
+use serde::Serialize;
 use sp_core::crypto::Pair;
-use tfchain_client::{types::BlockNumber, AccountId32};
+use std::fmt;
+use tfchain_client::calls::Extrinsic;
+use tfchain_client::client::{ApiResult, Included, SharedClient};
+use tfchain_client::events::{BlockEvent, Phase};
+use tfchain_client::filter::EventFilter;
+use tfchain_client::types::{
+    AccountDataExt, BlockNumber, Contract, ContractData, ContractState, Farm, FarmCertification,
+    Hash, Node, NodeCertification, Resources, Tft,
+};
+use tfchain_client::window::{BlockStream, Window, WindowResult};
+use tfchain_client::{AccountId32, Endpoints, Error, RetryPolicy};
+
+/// The key pair and client used by the cli.
+type CliPair = sp_core::sr25519::Pair;
+type Cli = SharedClient<CliPair, runtime_legacy::Event>;
 
 const GIT_VERSION: &str = git_version!(args = ["--tags", "--always", "--dirty=-modified"]);
 
@@ -16,7 +34,9 @@ fn main() {
                 .short('s')
                 .long("websocket")
                 .default_value("wss://tfchain.dev.grid.tf")
-                .help("substrate websocket connection"),
+                .multiple_occurrences(true)
+                .use_delimiter(true)
+                .help("substrate websocket connection, repeat for failover endpoints"),
         )
         .arg(
             Arg::new("mnemonic")
@@ -25,25 +45,159 @@ fn main() {
                 .long("mnemonic")
                 .help("mnemonic words"),
         )
+        .arg(
+            Arg::new("output")
+                .value_name("FORMAT")
+                .short('o')
+                .long("output")
+                .possible_values(output::FORMATS)
+                .default_value("text")
+                .global(true)
+                .help("the format in which to print the output"),
+        )
+        .arg(
+            Arg::new("at_height")
+                .value_name("HEIGHT")
+                .long("at-height")
+                .global(true)
+                .help("get and list commands read the state at the block with this height"),
+        )
+        .arg(
+            Arg::new("at_block")
+                .value_name("HASH")
+                .long("at-block")
+                .global(true)
+                .conflicts_with("at_height")
+                .help("get and list commands read the state at the block with this hash"),
+        )
+        .arg(
+            Arg::new("at_time")
+                .value_name("TIMESTAMP")
+                .long("at-time")
+                .global(true)
+                .conflicts_with_all(&["at_height", "at_block"])
+                .help("get and list commands read the state at this unix timestamp"),
+        )
         .subcommand(
-            App::new("farms").about("Farm operations").subcommand(
-                App::new("get").about("Get farm").arg(
-                    Arg::new("farm_id")
-                        .help("get by id")
-                        .takes_value(true)
-                        .required(true),
-                ),
-            ),
+            App::new("farms")
+                .about("Farm operations")
+                .subcommand(
+                    App::new("get").about("Get farm").arg(
+                        Arg::new("farm_id")
+                            .help("get by id")
+                            .takes_value(true)
+                            .required(true),
+                    ),
+                )
+                .subcommand(
+                    App::new("list")
+                        .about("List farms")
+                        .arg(twin_filter_arg())
+                        .arg(
+                            Arg::new("certified")
+                                .long("certified")
+                                .help("only list gold certified farms"),
+                        ),
+                )
+                .subcommand(
+                    App::new("update")
+                        .about("Update the name and pricing policy of a farm")
+                        .arg(farm_id_arg())
+                        .arg(
+                            Arg::new("name")
+                                .help("the new name of the farm")
+                                .takes_value(true)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("pricing_policy_id")
+                                .help("the id of the pricing policy of the farm")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("add-ip")
+                        .about("Add a public IP to a farm")
+                        .arg(farm_id_arg())
+                        .arg(
+                            Arg::new("ip")
+                                .help("the IP to add, in CIDR notation")
+                                .takes_value(true)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("gateway")
+                                .help("the gateway of the IP")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("remove-ip")
+                        .about("Remove a public IP from a farm")
+                        .arg(farm_id_arg())
+                        .arg(
+                            Arg::new("ip")
+                                .help("the IP to remove, in CIDR notation")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("payout-address")
+                        .about("Set the stellar address farming rewards are paid out to")
+                        .arg(farm_id_arg())
+                        .arg(
+                            Arg::new("address")
+                                .help("the stellar address")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("set-dedicated")
+                        .about("Mark a farm as dedicated or not, requires the root key")
+                        .arg(farm_id_arg())
+                        .arg(
+                            Arg::new("dedicated")
+                                .help("true to mark the farm as dedicated, false otherwise")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                )
+                .subcommand(App::new("delete").about("Delete a farm").arg(farm_id_arg())),
         )
         .subcommand(
-            App::new("balance").about("Balance operations").subcommand(
-                App::new("get").about("Get balance").arg(
-                    Arg::new("account")
-                        .help("the account for which to query the balance")
-                        .takes_value(true)
-                        .required(true),
+            App::new("balance")
+                .about("Balance operations")
+                .subcommand(
+                    App::new("get").about("Get balance").arg(
+                        Arg::new("account")
+                            .help("the account for which to query the balance")
+                            .takes_value(true)
+                            .required(true),
+                    ),
+                )
+                .subcommand(
+                    App::new("transfer")
+                        .about("Transfer TFT to another account")
+                        .arg(
+                            Arg::new("to")
+                                .help("the account receiving the TFT")
+                                .takes_value(true)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("amount")
+                                .help("the amount of TFT to transfer, e.g. 12.5")
+                                .takes_value(true)
+                                .required(true),
+                        )
+                        .arg(Arg::new("keep_alive").long("keep-alive").help(
+                            "fail instead of removing your account if its balance drops too low",
+                        )),
                 ),
-            ),
         )
         .subcommand(
             App::new("node")
@@ -55,6 +209,27 @@ fn main() {
                             .takes_value(true)
                             .required(true),
                     ),
+                )
+                .subcommand(
+                    App::new("list")
+                        .about("List nodes")
+                        .arg(
+                            Arg::new("farm")
+                                .value_name("FARM_ID")
+                                .long("farm")
+                                .help("only list nodes in this farm"),
+                        )
+                        .arg(twin_filter_arg())
+                        .arg(
+                            Arg::new("certified")
+                                .long("certified")
+                                .help("only list certified nodes"),
+                        )
+                        .arg(
+                            Arg::new("public_config")
+                                .long("public-config")
+                                .help("only list nodes with a public config"),
+                        ),
                 ),
         )
         .subcommand(
@@ -67,6 +242,30 @@ fn main() {
                             .takes_value(true)
                             .required(true),
                     ),
+                )
+                .subcommand(
+                    App::new("list")
+                        .about("List contracts")
+                        .arg(twin_filter_arg())
+                        .arg(
+                            Arg::new("node")
+                                .value_name("NODE_ID")
+                                .long("node")
+                                .help("only list node and rent contracts on this node"),
+                        )
+                        .arg(
+                            Arg::new("state")
+                                .value_name("STATE")
+                                .long("state")
+                                .possible_values(["created", "deleted", "grace-period"])
+                                .help("only list contracts in this state"),
+                        )
+                        .arg(
+                            Arg::new("live")
+                                .long("live")
+                                .conflicts_with("state")
+                                .help("only list contracts which are currently deployed"),
+                        ),
                 ),
         )
         .subcommand(
@@ -87,6 +286,22 @@ fn main() {
                             .takes_value(true)
                             .required(true),
                     ),
+                )
+                .subcommand(
+                    App::new("update").about("Update the IP of your twin").arg(
+                        Arg::new("ip")
+                            .help("The new IP of the twin")
+                            .takes_value(true)
+                            .required(true),
+                    ),
+                )
+                .subcommand(
+                    App::new("delete").about("Delete a twin").arg(
+                        Arg::new("twin_id")
+                            .help("the id of the twin to delete")
+                            .takes_value(true)
+                            .required(true),
+                    ),
                 ),
         )
         .subcommand(
@@ -112,7 +327,41 @@ fn main() {
         .subcommand(
             App::new("subscribe")
                 .about("Subscriptions on chain")
-                .subcommand(App::new("finalized").about("Subscribe to finalized heads")),
+                .subcommand(
+                    App::new("finalized")
+                        .about("Subscribe to finalized blocks")
+                        .arg(
+                            Arg::new("from")
+                                .value_name("HEIGHT")
+                                .long("from")
+                                .help("start at this height instead of the next finalized block"),
+                        ),
+                )
+                .subcommand(
+                    App::new("events")
+                        .about("Subscribe to the events in finalized blocks matching a filter")
+                        .arg(event_filter_arg(
+                            "pallet",
+                            "NAME",
+                            "the pallet, e.g. SmartContract",
+                        ))
+                        .arg(event_filter_arg(
+                            "variant",
+                            "NAME",
+                            "the event, e.g. ContractCreated",
+                        ))
+                        .arg(event_filter_arg("twin", "TWIN_ID", "the twin"))
+                        .arg(event_filter_arg("node", "NODE_ID", "the node"))
+                        .arg(event_filter_arg("farm", "FARM_ID", "the farm"))
+                        .arg(event_filter_arg("contract", "CONTRACT_ID", "the contract"))
+                        .arg(event_filter_arg("account", "ACCOUNT", "the account"))
+                        .arg(
+                            Arg::new("from")
+                                .value_name("HEIGHT")
+                                .long("from")
+                                .help("start at this height instead of the next finalized block"),
+                        ),
+                ),
         )
         .subcommand(
             App::new("time")
@@ -121,144 +370,794 @@ fn main() {
         )
         .get_matches();
 
-    let websocket = matches.value_of("websocket").unwrap();
+    let websockets: Vec<String> = matches
+        .values_of("websocket")
+        .unwrap()
+        .map(String::from)
+        .collect();
+    let out = Output::new(matches.value_of_t::<Format>("output").unwrap());
     let key: (sp_core::sr25519::Pair, _) = Pair::generate();
-    let mut client = tfchain_client::Client::<_, runtime_legacy::Event>::new(
-        String::from(websocket),
-        Some(key.0),
-    );
+    let mut key = key.0;
 
     // if mnemonic provided, load client with words
     if let Some(mnemonic) = matches.values_of("mnemonic") {
         let words: String = mnemonic.collect();
-        let key: (sp_core::sr25519::Pair, _) = Pair::from_phrase(words.as_str(), None).unwrap();
-        client = tfchain_client::Client::new(String::from(websocket), Some(key.0));
+        match sp_core::sr25519::Pair::from_phrase(words.as_str(), None) {
+            Ok((pair, _)) => key = pair,
+            Err(e) => {
+                eprintln!("invalid mnemonic: {:?}", e);
+                std::process::exit(1);
+            }
+        }
     }
 
+    let endpoints = match Endpoints::new(websockets.clone()) {
+        Ok(endpoints) => endpoints,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let client = match tfchain_client::Client::<_, runtime_legacy::Event>::with_endpoints(
+        endpoints,
+        Some(key),
+        RetryPolicy::default(),
+    ) {
+        Ok(client) => SharedClient::new(client),
+        Err(e) => {
+            eprintln!("could not connect to {}: {}", websockets.join(", "), e);
+            std::process::exit(1);
+        }
+    };
+
+    // The historic block read commands query, the latest block if None.
+    let at = match resolve_block(&client, &matches) {
+        Ok(at) => at,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let block = at.map(|(_, hash)| hash);
+
     match matches.subcommand() {
         Some(("farms", farm_matches)) => {
             if let Some(get_farm) = farm_matches.subcommand_matches("get") {
                 match get_farm.value_of_t("farm_id") {
-                    Ok(farm_id) => {
-                        let farm = client.get_farm_by_id(farm_id, None).unwrap();
-                        match farm {
-                            Some(farm) => println!("{}", farm),
-                            None => println!("farm with id {} does not exist", farm_id),
-                        }
+                    Ok(farm_id) => match client.get_farm_by_id(farm_id, block) {
+                        Ok(Some(farm)) => out.value(&farm, || farm.to_string()),
+                        Ok(None) => out.error(format!("farm with id {} does not exist", farm_id)),
+                        Err(e) => out.error(format!("could not get farm: {}", e)),
+                    },
+                    Err(e) => out.error(format!("could not find farm: {}", e)),
+                }
+            }
+            if let Some(list) = farm_matches.subcommand_matches("list") {
+                match (window_at(&client, at), optional_arg(list, "twin")) {
+                    (Ok(window), Ok(twin)) => {
+                        let certified = list.is_present("certified");
+                        let farms = collect_filtered(window.farms(), |farm: &Farm| {
+                            twin.map_or(true, |id| farm.twin_id == id)
+                                && (!certified || farm.certification == FarmCertification::Gold)
+                        });
+                        print_list(&out, "farms", farms)
                     }
-                    Err(e) => {
-                        println!("could not find farm: {}", e);
+                    (Err(e), _) | (_, Err(e)) => out.error(e),
+                }
+            }
+            if let Some(update) = farm_matches.subcommand_matches("update") {
+                match (
+                    update.value_of_t("farm_id"),
+                    update.value_of_t("pricing_policy_id"),
+                ) {
+                    (Ok(farm_id), Ok(pricing_policy_id)) => print_farm_update(
+                        &out,
+                        client.update_farm(
+                            farm_id,
+                            update.value_of("name").unwrap(),
+                            pricing_policy_id,
+                        ),
+                    ),
+                    (Err(e), _) | (_, Err(e)) => {
+                        out.error(format!("could not parse argument: {}", e))
                     }
                 }
             }
+            if let Some(add_ip) = farm_matches.subcommand_matches("add-ip") {
+                match add_ip.value_of_t("farm_id") {
+                    Ok(farm_id) => print_farm_update(
+                        &out,
+                        client.add_farm_ip(
+                            farm_id,
+                            add_ip.value_of("ip").unwrap(),
+                            add_ip.value_of("gateway").unwrap(),
+                        ),
+                    ),
+                    Err(e) => out.error(format!("could not parse farm_id: {}", e)),
+                }
+            }
+            if let Some(remove_ip) = farm_matches.subcommand_matches("remove-ip") {
+                match remove_ip.value_of_t("farm_id") {
+                    Ok(farm_id) => print_farm_update(
+                        &out,
+                        client.remove_farm_ip(farm_id, remove_ip.value_of("ip").unwrap()),
+                    ),
+                    Err(e) => out.error(format!("could not parse farm_id: {}", e)),
+                }
+            }
+            if let Some(payout) = farm_matches.subcommand_matches("payout-address") {
+                match payout.value_of_t("farm_id") {
+                    Ok(farm_id) => print_farm_update(
+                        &out,
+                        client.add_stellar_payout_v2address(
+                            farm_id,
+                            payout.value_of("address").unwrap(),
+                        ),
+                    ),
+                    Err(e) => out.error(format!("could not parse farm_id: {}", e)),
+                }
+            }
+            if let Some(dedicated) = farm_matches.subcommand_matches("set-dedicated") {
+                match (
+                    dedicated.value_of_t("farm_id"),
+                    dedicated.value_of_t("dedicated"),
+                ) {
+                    (Ok(farm_id), Ok(flag)) => {
+                        print_farm_update(&out, client.set_farm_dedicated(farm_id, flag))
+                    }
+                    (Err(e), _) | (_, Err(e)) => {
+                        out.error(format!("could not parse argument: {}", e))
+                    }
+                }
+            }
+            if let Some(delete) = farm_matches.subcommand_matches("delete") {
+                match delete.value_of_t("farm_id") {
+                    Ok(farm_id) => match client.delete_farm(farm_id) {
+                        Ok(included) => out.value(&included, || {
+                            format!("farm {} deleted in block {:?}", farm_id, included.block)
+                        }),
+                        Err(e) => out.error(format!("could not delete farm: {}", e)),
+                    },
+                    Err(e) => out.error(format!("could not parse farm_id: {}", e)),
+                }
+            }
         }
         Some(("balance", account)) => {
             if let Some(get_balance) = account.subcommand_matches("get") {
                 let account = get_balance.value_of("account").unwrap();
                 match account.parse::<AccountId32>() {
-                    Ok(ref account) => {
-                        let balance = client.get_account_free_balance(account).unwrap();
-                        let info = format!(
-                            "{}.{}",
-                            balance.free / 1e7 as u128,
-                            balance.free % 1e7 as u128
-                        );
-                        println!("Free balance for account {}: {} TFT", account, info);
-                    }
-                    Err(e) => {
-                        println!("{} is not a valid account ({})", account, e);
+                    Ok(ref account) => match client.get_account_free_balance(account, block) {
+                        Ok(data) => {
+                            let balance = Balance {
+                                account,
+                                free: data.free_tft(),
+                                reserved: data.reserved_tft(),
+                                frozen: data.frozen_tft(),
+                                transferable: data.transferable_tft(),
+                            };
+                            out.value(&balance, || {
+                                format!(
+                                    "Free balance for account {}: {} TFT",
+                                    account, balance.free
+                                )
+                            });
+                        }
+                        Err(e) => out.error(format!("could not get balance: {}", e)),
+                    },
+                    Err(e) => out.error(format!("{} is not a valid account ({})", account, e)),
+                }
+            }
+            if let Some(transfer) = account.subcommand_matches("transfer") {
+                let to = transfer.value_of("to").unwrap();
+                match (
+                    to.parse::<AccountId32>(),
+                    transfer.value_of_t::<Tft>("amount"),
+                ) {
+                    (Ok(ref to), Ok(amount)) => {
+                        let res = if transfer.is_present("keep_alive") {
+                            client.transfer_keep_alive(to, amount)
+                        } else {
+                            client.transfer(to, amount)
+                        };
+                        match res {
+                            Ok(included) => out.value(&included, || {
+                                format!(
+                                    "transferred {} TFT to {} in block {:?}",
+                                    amount, to, included.block
+                                )
+                            }),
+                            Err(e) => out.error(format!("could not transfer: {}", e)),
+                        }
                     }
+                    (Err(e), _) => out.error(format!("{} is not a valid account ({})", to, e)),
+                    (_, Err(e)) => out.error(format!("could not parse amount: {}", e)),
                 }
             }
         }
         Some(("node", node_data)) => {
             if let Some(get_node) = node_data.subcommand_matches("get") {
                 match get_node.value_of_t("node_id") {
-                    Ok(node_id) => match client.get_node_by_id(node_id, None).unwrap() {
-                        Some(node) => println!("{}", node),
-                        None => println!("no node found with id {}", node_id),
+                    Ok(node_id) => match client.get_node_by_id(node_id, block) {
+                        Ok(Some(node)) => out.value(&node, || node.to_string()),
+                        Ok(None) => out.error(format!("no node found with id {}", node_id)),
+                        Err(e) => out.error(format!("could not get node: {}", e)),
                     },
-                    Err(e) => println!("could not parse node_id: {}", e),
+                    Err(e) => out.error(format!("could not parse node_id: {}", e)),
+                }
+            }
+            if let Some(list) = node_data.subcommand_matches("list") {
+                match (
+                    window_at(&client, at),
+                    optional_arg(list, "farm"),
+                    optional_arg(list, "twin"),
+                ) {
+                    (Ok(window), Ok(farm), Ok(twin)) => {
+                        let certified = list.is_present("certified");
+                        let public_config = list.is_present("public_config");
+                        let nodes = collect_filtered(window.nodes(), |node: &Node| {
+                            farm.map_or(true, |id| node.farm_id == id)
+                                && twin.map_or(true, |id| node.twin_id == id)
+                                && (!certified
+                                    || node.certification == NodeCertification::Certified)
+                                && (!public_config || node.public_config.is_some())
+                        });
+                        print_list(&out, "nodes", nodes)
+                    }
+                    (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => out.error(e),
                 }
             }
         }
         Some(("contract", contract_data)) => {
             if let Some(get_contract) = contract_data.subcommand_matches("get") {
                 match get_contract.value_of_t("contract_id") {
-                    Ok(contract_id) => {
-                        match client.get_contract_by_id(contract_id, None).unwrap() {
-                            Some(contract) => println!("{}", contract),
-                            None => println!("no contract found with id {}", contract_id),
-                        };
+                    Ok(contract_id) => match client.get_contract_by_id(contract_id, block) {
+                        Ok(Some(contract)) => out.value(&contract, || contract.to_string()),
+                        Ok(None) => out.error(format!("no contract found with id {}", contract_id)),
+                        Err(e) => out.error(format!("could not get contract: {}", e)),
+                    },
+                    Err(e) => out.error(format!("could not parse contract_id: {}", e)),
+                }
+            }
+            if let Some(list) = contract_data.subcommand_matches("list") {
+                match (
+                    window_at(&client, at),
+                    optional_arg(list, "twin"),
+                    optional_arg(list, "node"),
+                ) {
+                    (Ok(window), Ok(twin), Ok(node)) => {
+                        let live = list.is_present("live");
+                        let state = list.value_of("state");
+                        let contracts = collect_filtered(
+                            window.contracts(live),
+                            |(contract, _): &(Contract, Resources)| {
+                                twin.map_or(true, |id| contract.twin_id == id)
+                                    && node.map_or(true, |id| contract_node(contract) == Some(id))
+                                    && state.map_or(true, |state| contract_state(contract) == state)
+                            },
+                        )
+                        .map(|contracts| {
+                            contracts
+                                .into_iter()
+                                .map(|(contract, resources)| ContractListItem {
+                                    contract,
+                                    resources,
+                                })
+                                .collect::<Vec<_>>()
+                        });
+                        print_list(&out, "contracts", contracts)
                     }
-                    Err(e) => println!("could not parse contract_id: {}", e),
+                    (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => out.error(e),
                 }
             }
         }
         Some(("twin", twin_data)) => {
             if let Some(get_twin) = twin_data.subcommand_matches("get") {
                 match get_twin.value_of_t("twin_id") {
-                    Ok(twin_id) => {
-                        let twin = client.get_twin_by_id(twin_id).unwrap();
-                        println!("{}", twin);
-                    }
-                    Err(e) => println!("could not parse twin_id: {}", e),
+                    Ok(twin_id) => match client.get_twin_by_id(twin_id, block) {
+                        Ok(Some(twin)) => out.value(&twin, || twin.to_string()),
+                        Ok(None) => out.error(format!("no twin found with id {}", twin_id)),
+                        Err(e) => out.error(format!("could not get twin: {}", e)),
+                    },
+                    Err(e) => out.error(format!("could not parse twin_id: {}", e)),
                 }
             }
             if let Some(create_twin) = twin_data.subcommand_matches("create") {
                 match create_twin.value_of_t::<String>("ip") {
-                    Ok(ip) => {
-                        let hash = client.create_twin(&ip).unwrap();
-                        println!("transaction included in blockhash: {:?}", hash);
-                    }
-                    Err(e) => println!("could not parse ip: {}", e),
+                    Ok(ip) => match client.create_twin(&ip) {
+                        Ok(receipt) => out.value(&receipt, || {
+                            format!(
+                                "transaction included in block {:?} at index {}: {:?}",
+                                receipt.block, receipt.extrinsic_index, receipt.outcome
+                            )
+                        }),
+                        Err(e) => out.error(format!("could not create twin: {}", e)),
+                    },
+                    Err(e) => out.error(format!("could not parse ip: {}", e)),
+                }
+            }
+            if let Some(update_twin) = twin_data.subcommand_matches("update") {
+                match update_twin.value_of_t::<String>("ip") {
+                    Ok(ip) => match client.update_twin(&ip) {
+                        Ok(included) => out.value(&included, || {
+                            format!(
+                                "twin updated in block {:?}: {:?}",
+                                included.block, included.event
+                            )
+                        }),
+                        Err(e) => out.error(format!("could not update twin: {}", e)),
+                    },
+                    Err(e) => out.error(format!("could not parse ip: {}", e)),
+                }
+            }
+            if let Some(delete_twin) = twin_data.subcommand_matches("delete") {
+                match delete_twin.value_of_t("twin_id") {
+                    Ok(twin_id) => match client.delete_twin(twin_id) {
+                        Ok(included) => out.value(&included, || {
+                            format!("twin {} deleted in block {:?}", twin_id, included.block)
+                        }),
+                        Err(e) => out.error(format!("could not delete twin: {}", e)),
+                    },
+                    Err(e) => out.error(format!("could not parse twin_id: {}", e)),
                 }
             }
         }
         Some(("block", block_data)) => {
             if let Some(get_block) = block_data.subcommand_matches("get") {
                 match get_block.value_of("block_hash") {
-                    Some(block_hash) => {
-                        let block = client.get_block_by_hash(block_hash).unwrap().unwrap();
-                        println!("Block:\n{:#?}", block);
-                        println!(
-                            "Events:\n{:#?}",
-                            client.get_block_events(Some(block.header.hash())).unwrap()
-                        );
-                    }
-                    None => println!("Missing block hash"),
+                    Some(block_hash) => match block_output(&client, block_hash) {
+                        Ok(Some(block)) => out.value(&block, || block.to_string()),
+                        Ok(None) => out.error(format!("no block found with hash {}", block_hash)),
+                        Err(e) => out.error(format!("could not get block: {}", e)),
+                    },
+                    None => out.error("Missing block hash"),
                 }
             } else if let Some(get_hash) = block_data.subcommand_matches("height") {
                 match get_hash.value_of("block_height") {
-                    Some(height) => {
-                        let height = height.parse::<BlockNumber>().unwrap();
-                        match client.get_hash_at_height(height).unwrap() {
-                            Some(hash) => println!("Hash at height {} is {}", height, hash),
-                            None => println!("No block at height {}", height),
-                        }
-                    }
-                    None => println!("Missing block height"),
+                    Some(height) => match height.parse::<BlockNumber>() {
+                        Ok(height) => match client.get_hash_at_height(height) {
+                            Ok(Some(hash)) => out.value(&BlockHash { height, hash }, || {
+                                format!("Hash at height {} is {}", height, hash)
+                            }),
+                            Ok(None) => out.error(format!("No block at height {}", height)),
+                            Err(e) => out.error(format!("could not get block hash: {}", e)),
+                        },
+                        Err(e) => out.error(format!("could not parse block_height: {}", e)),
+                    },
+                    None => out.error("Missing block height"),
                 }
             }
         }
         Some(("subscribe", sub_data)) => {
-            if let Some(_) = sub_data.subcommand_matches("finalized") {
-                let res = client.finalized_block_headers().unwrap();
-                for head in res {
-                    println!("{:?}", head);
-                    for event in client.get_block_events(Some(head.hash())).unwrap() {
-                        println!("{:?}", event);
+            if let Some(finalized) = sub_data.subcommand_matches("finalized") {
+                let from = match optional_arg(finalized, "from") {
+                    Ok(from) => from,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+                for res in BlockStream::new(client.clone(), from) {
+                    let block = match res {
+                        Ok(block) => block,
+                        Err(e) => {
+                            out.error(format!("could not get finalized block: {}", e));
+                            // The same block is tried again after a transient error, any other
+                            // error would just repeat.
+                            if e.is_transient() {
+                                continue;
+                            }
+                            break;
+                        }
+                    };
+                    out.item(&block, || {
+                        let mut text = format!(
+                            "Block {} ({:?}) at {}",
+                            block.height,
+                            block.hash,
+                            block.date()
+                        );
+                        for event in &block.events {
+                            text.push_str(&format!("\n{:?}: {:?}", event.phase, event.event));
+                        }
+                        text
+                    });
+                }
+            }
+            if let Some(events) = sub_data.subcommand_matches("events") {
+                let (filter, from) = match (event_filter(events), optional_arg(events, "from")) {
+                    (Ok(filter), Ok(from)) => (filter, from),
+                    (Err(e), _) | (_, Err(e)) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+                for res in BlockStream::new(client.clone(), from).with_filter(filter) {
+                    let block = match res {
+                        Ok(block) => block,
+                        Err(e) => {
+                            out.error(format!("could not get finalized block: {}", e));
+                            // The same block is tried again after a transient error, any other
+                            // error would just repeat.
+                            if e.is_transient() {
+                                continue;
+                            }
+                            break;
+                        }
+                    };
+                    for event in &block.events {
+                        let item = MatchedEvent {
+                            height: block.height,
+                            hash: block.hash,
+                            event,
+                        };
+                        out.item(&item, || {
+                            format!(
+                                "Block {} {:?}: {:?}",
+                                block.height, event.phase, event.event
+                            )
+                        });
                     }
                 }
             }
         }
         Some(("time", ts)) => match ts.value_of_t("timestamp") {
-            Err(e) => println!("Could not read timestamp {}", e),
-            Ok(ts) => match client.height_at_timestamp(ts) {
-                Ok(height) => println!("timestamp {} is at height {}", ts, height),
-                Err(e) => println!("could not get height of timestamp {}: {}", ts, e),
+            Err(e) => out.error(format!("Could not read timestamp {}", e)),
+            Ok(timestamp) => match client.height_at_timestamp(timestamp) {
+                Ok(height) => out.value(&TimeHeight { timestamp, height }, || {
+                    format!("timestamp {} is at height {}", timestamp, height)
+                }),
+                Err(e) => out.error(format!(
+                    "could not get height of timestamp {}: {}",
+                    timestamp, e
+                )),
             },
         },
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
     };
+
+    if out.failed() {
+        std::process::exit(1);
+    }
+}
+
+fn farm_id_arg() -> Arg<'static> {
+    Arg::new("farm_id")
+        .help("the id of the farm")
+        .takes_value(true)
+        .required(true)
+}
+
+fn twin_filter_arg() -> Arg<'static> {
+    Arg::new("twin")
+        .value_name("TWIN_ID")
+        .long("twin")
+        .help("only list items owned by this twin")
+}
+
+fn event_filter_arg(name: &'static str, value: &'static str, help: &'static str) -> Arg<'static> {
+    Arg::new(name)
+        .value_name(value)
+        .long(name)
+        .multiple_occurrences(true)
+        .help(help)
+}
+
+/// Build the [EventFilter] from the arguments of `subscribe events`. Every argument can be
+/// repeated to match any of the given values.
+fn event_filter(matches: &ArgMatches) -> Result<EventFilter, String> {
+    fn values<T>(matches: &ArgMatches, name: &str) -> Result<Vec<T>, String>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        matches
+            .values_of(name)
+            .into_iter()
+            .flatten()
+            .map(|v| {
+                v.parse()
+                    .map_err(|e| format!("could not parse {} {}: {}", name, v, e))
+            })
+            .collect()
+    }
+
+    let mut filter = EventFilter::new();
+    for pallet in matches.values_of("pallet").into_iter().flatten() {
+        filter = filter.with_pallet(pallet);
+    }
+    for variant in matches.values_of("variant").into_iter().flatten() {
+        filter = filter.with_variant(variant);
+    }
+    for twin in values(matches, "twin")? {
+        filter = filter.with_twin(twin);
+    }
+    for node in values(matches, "node")? {
+        filter = filter.with_node(node);
+    }
+    for farm in values(matches, "farm")? {
+        filter = filter.with_farm(farm);
+    }
+    for contract in values(matches, "contract")? {
+        filter = filter.with_contract(contract);
+    }
+    for account in values::<AccountId32>(matches, "account")? {
+        filter = filter.with_account(account);
+    }
+    Ok(filter)
+}
+
+/// Parse an optional argument, like an id to filter a list on or a height to start from.
+fn optional_arg<T>(matches: &ArgMatches, name: &str) -> Result<Option<T>, String>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    matches
+        .value_of(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|e| format!("could not parse {}: {}", name, e))
+        })
+        .transpose()
+}
+
+/// The commands which read state, and so support selecting a block with `--at-height`,
+/// `--at-block` or `--at-time`.
+const READ_COMMANDS: [(&str, &str); 8] = [
+    ("farms", "get"),
+    ("farms", "list"),
+    ("balance", "get"),
+    ("node", "get"),
+    ("node", "list"),
+    ("contract", "get"),
+    ("contract", "list"),
+    ("twin", "get"),
+];
+
+/// Resolve the block selected with `--at-height`, `--at-block` or `--at-time` to its height and
+/// hash. Returns None if no block is selected, in which case the latest state is read. Selecting
+/// a block for a command which does not read state is an error.
+fn resolve_block(
+    client: &Cli,
+    matches: &ArgMatches,
+) -> Result<Option<(BlockNumber, Hash)>, String> {
+    let selected = ["at_height", "at_block", "at_time"]
+        .iter()
+        .any(|flag| matches.is_present(flag));
+    let reads_state = matches.subcommand().map_or(false, |(cmd, sub)| {
+        sub.subcommand_name()
+            .map_or(false, |name| READ_COMMANDS.contains(&(cmd, name)))
+    });
+    if selected && !reads_state {
+        let commands: Vec<_> = READ_COMMANDS
+            .iter()
+            .map(|(cmd, sub)| format!("{} {}", cmd, sub))
+            .collect();
+        return Err(format!(
+            "--at-height, --at-block and --at-time are only supported by {}",
+            commands.join(", ")
+        ));
+    }
+
+    let height = if let Some(height) = matches.value_of("at_height") {
+        height
+            .parse::<BlockNumber>()
+            .map_err(|e| format!("could not parse at-height: {}", e))?
+    } else if let Some(hash) = matches.value_of("at_block") {
+        // Only the header is needed, which does not depend on the runtime version of the block.
+        return match client.get_header_by_hash(hash) {
+            Ok(Some(header)) => Ok(Some((header.number, header.hash()))),
+            Ok(None) => Err(format!("no block with hash {}", hash)),
+            Err(e) => Err(format!("could not get block {}: {}", hash, e)),
+        };
+    } else if let Some(ts) = matches.value_of("at_time") {
+        let ts = ts
+            .parse::<i64>()
+            .map_err(|e| format!("could not parse at-time: {}", e))?;
+        client
+            .height_at_timestamp(ts)
+            .map_err(|e| format!("could not get height of timestamp {}: {}", ts, e))?
+    } else {
+        return Ok(None);
+    };
+    match client.get_hash_at_height(height) {
+        Ok(Some(hash)) => Ok(Some((height, hash))),
+        Ok(None) => Err(format!("no block at height {}", height)),
+        Err(e) => Err(format!("could not get hash at height {}: {}", height, e)),
+    }
+}
+
+/// Get the [Window] at the block selected with the global flags, or at the latest finalized
+/// block if no block is selected.
+fn window_at(client: &Cli, at: Option<(BlockNumber, Hash)>) -> Result<Window<CliPair>, String> {
+    let height = match at {
+        Some((height, _)) => height,
+        None => client
+            .finalized_height()
+            .map_err(|e| format!("could not get the finalized height: {}", e))?,
+    };
+    match Window::at_height(client.clone(), height) {
+        Ok(Some(window)) => Ok(window),
+        Ok(None) => Err(format!("no block at height {}", height)),
+        Err(e) => Err(format!("could not get block at height {}: {}", height, e)),
+    }
+}
+
+/// Get a block by its hash, together with its extrinsics and the events which were not emitted
+/// by an extrinsic. The block is decoded with the types of the runtime which produced it.
+/// Returns `None` if there is no block with that hash.
+fn block_output(client: &Cli, block_hash: &str) -> WindowResult<Option<BlockOutput>> {
+    let header = match client.get_header_by_hash(block_hash)? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let hash = header.hash();
+    let window = match Window::at_height(client.clone(), header.number)? {
+        Some(window) if window.block_hash()? == hash => window,
+        _ => {
+            return Err(Error::NotFound(format!(
+                "block {:?} is not part of the canonical chain",
+                hash
+            ))
+            .into())
+        }
+    };
+    Ok(Some(BlockOutput {
+        height: header.number,
+        hash,
+        extrinsics: window.extrinsics()?,
+        events: window
+            .events()?
+            .into_iter()
+            .filter(|event| !matches!(event.phase, Phase::ApplyExtrinsic(_)))
+            .collect(),
+    }))
+}
+
+/// The node a node or rent contract is deployed on.
+fn contract_node(contract: &Contract) -> Option<u32> {
+    match &contract.contract_type {
+        ContractData::NodeContract(nc) => Some(nc.node_id),
+        ContractData::RentContract(rc) => Some(rc.node_id),
+        ContractData::NameContract(_) => None,
+    }
+}
+
+/// The name of the state of a contract, as accepted by `contract list --state`.
+fn contract_state(contract: &Contract) -> &'static str {
+    match contract.state {
+        ContractState::Created => "created",
+        ContractState::Deleted(_) => "deleted",
+        ContractState::GracePeriod(_) => "grace-period",
+    }
+}
+
+/// Collect the items returned by a [Window] iterator which match a filter.
+fn collect_filtered<I, T, F>(items: WindowResult<I>, filter: F) -> WindowResult<Vec<T>>
+where
+    I: Iterator<Item = WindowResult<T>>,
+    F: Fn(&T) -> bool,
+{
+    let mut matched = Vec::new();
+    for item in items? {
+        let item = item?;
+        if filter(&item) {
+            matched.push(item);
+        }
+    }
+    Ok(matched)
+}
+
+fn print_list<T>(out: &Output, kind: &str, res: WindowResult<Vec<T>>)
+where
+    T: Serialize + fmt::Display,
+{
+    match res {
+        Ok(items) => out.value(&items, || {
+            items
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        }),
+        Err(e) => out.error(format!("could not list {}: {}", kind, e)),
+    }
+}
+
+fn print_farm_update(out: &Output, res: ApiResult<Included<Farm>>) {
+    match res {
+        Ok(included) => out.value(&included, || {
+            format!(
+                "farm updated in block {:?}\n{}",
+                included.block, included.event
+            )
+        }),
+        Err(e) => out.error(format!("could not update farm: {}", e)),
+    }
+}
+
+/// The balance of an account, as printed by `balance get`.
+#[derive(Serialize)]
+struct Balance<'a> {
+    account: &'a AccountId32,
+    free: Tft,
+    reserved: Tft,
+    frozen: Tft,
+    transferable: Tft,
+}
+
+/// A block with its extrinsics, as printed by `block get`. The events of the block which were
+/// not emitted by an extrinsic are listed separately.
+#[derive(Serialize)]
+struct BlockOutput {
+    height: BlockNumber,
+    hash: Hash,
+    extrinsics: Vec<Extrinsic>,
+    events: Vec<BlockEvent>,
+}
+
+impl fmt::Display for BlockOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Block {} ({:?})", self.height, self.hash)?;
+        for xt in &self.extrinsics {
+            match &xt.signer {
+                Some(signer) => write!(
+                    f,
+                    "\nExtrinsic {} by {} (nonce {}, tip {} TFT): {:?}",
+                    xt.index,
+                    signer.account,
+                    signer.nonce,
+                    signer.tip,
+                    xt.outcome()
+                )?,
+                None => write!(f, "\nExtrinsic {} (unsigned)", xt.index)?,
+            }
+            write!(f, "\n{:#?}", xt.call)?;
+            for event in &xt.events {
+                write!(f, "\n  {:?}", event)?;
+            }
+        }
+        for event in &self.events {
+            write!(f, "\n{:?}: {:?}", event.phase, event.event)?;
+        }
+        Ok(())
+    }
+}
+
+/// An event matching the filter of `subscribe events`, with the block it was emitted in.
+#[derive(Serialize)]
+struct MatchedEvent<'a> {
+    height: BlockNumber,
+    hash: Hash,
+    #[serde(flatten)]
+    event: &'a BlockEvent,
+}
+
+/// The hash of the block at a height, as printed by `block height`.
+#[derive(Serialize)]
+struct BlockHash {
+    height: BlockNumber,
+    hash: Hash,
+}
+
+/// The height of the block at a timestamp, as printed by `time`.
+#[derive(Serialize)]
+struct TimeHeight {
+    timestamp: i64,
+    height: BlockNumber,
+}
+
+/// A contract with its used resources, as listed by `contract list`.
+#[derive(Serialize)]
+struct ContractListItem {
+    #[serde(flatten)]
+    contract: Contract,
+    resources: Resources,
+}
+
+impl fmt::Display for ContractListItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\nresources: {:?}", self.contract, self.resources)
+    }
 }
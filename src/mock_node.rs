@@ -0,0 +1,131 @@
+//! A stand-in for a tfchain node in tests. It answers the rpc requests needed to connect a
+//! [Client](crate::client::Client) and to follow the finalized chain, with blocks that only
+//! consist of a header.
+
+use crate::types::{BlockNumber, Hash};
+use codec::Encode;
+use serde_json::{json, Value};
+use sp_runtime::traits::Header as _;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// A running stand-in node. The node is stopped when it is dropped.
+pub(crate) struct MockNode {
+    url: String,
+    finalized: Arc<AtomicU32>,
+    server: ws::Sender,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockNode {
+    /// Start a node on a free local port, with the given height as finalized head.
+    pub(crate) fn start(finalized: BlockNumber) -> Self {
+        let finalized = Arc::new(AtomicU32::new(finalized));
+        let height = finalized.clone();
+        let server = ws::Builder::new()
+            .build(move |out: ws::Sender| {
+                let height = height.clone();
+                move |msg: ws::Message| {
+                    let req: Value = serde_json::from_str(msg.as_text()?).unwrap_or_default();
+                    let result = answer(&req, height.load(Ordering::SeqCst));
+                    out.send(
+                        json!({ "jsonrpc": "2.0", "result": result, "id": req["id"] }).to_string(),
+                    )
+                }
+            })
+            .unwrap()
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let url = format!("ws://{}", server.local_addr().unwrap());
+        let handle = server.broadcaster();
+        let thread = thread::spawn(move || {
+            let _ = server.run();
+        });
+        MockNode {
+            url,
+            finalized,
+            server: handle,
+            thread: Some(thread),
+        }
+    }
+
+    /// The url to connect to this node.
+    pub(crate) fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Change the height of the finalized head.
+    pub(crate) fn set_finalized(&self, height: BlockNumber) {
+        self.finalized.store(height, Ordering::SeqCst);
+    }
+
+    /// Stop the node, and wait until it no longer accepts connections.
+    pub(crate) fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.server.shutdown();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// The hash the stand-in node uses for the block at the given height.
+pub(crate) fn hash(height: BlockNumber) -> Hash {
+    Hash::from_low_u64_be(height as u64 + 1)
+}
+
+fn height(hash: &Value) -> Option<BlockNumber> {
+    let hash = hash.as_str()?.trim_start_matches("0x");
+    let low = u64::from_str_radix(&hash[hash.len().checked_sub(16)?..], 16).ok()?;
+    low.checked_sub(1).map(|h| h as BlockNumber)
+}
+
+fn hex_hash(height: BlockNumber) -> String {
+    format!("0x{}", hex::encode(hash(height).as_bytes()))
+}
+
+/// The result of a single request, given the current finalized height. Unknown methods and
+/// blocks result in `null`.
+fn answer(req: &Value, finalized: BlockNumber) -> Value {
+    let params = &req["params"];
+    match req["method"].as_str().unwrap_or_default() {
+        "chain_getBlockHash" => match params[0].as_u64() {
+            Some(n) if n <= finalized as u64 => json!(hex_hash(n as BlockNumber)),
+            _ => Value::Null,
+        },
+        "chain_getFinalizedHead" => json!(hex_hash(finalized)),
+        "chain_getHeader" => {
+            let number = match params.get(0).filter(|h| !h.is_null()) {
+                Some(hash) => match height(hash) {
+                    Some(number) if number <= finalized => number,
+                    _ => return Value::Null,
+                },
+                None => finalized,
+            };
+            let header = runtime::Header::new(
+                number,
+                Default::default(),
+                Default::default(),
+                hash(number.saturating_sub(1)),
+                Default::default(),
+            );
+            serde_json::to_value(&header).unwrap()
+        }
+        "state_getMetadata" => json!(format!(
+            "0x{}",
+            hex::encode(runtime::Runtime::metadata().encode())
+        )),
+        "state_getRuntimeVersion" => serde_json::to_value(&runtime::VERSION).unwrap(),
+        _ => Value::Null,
+    }
+}
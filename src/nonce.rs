@@ -0,0 +1,132 @@
+//! Local tracking of the nonces of the signers of [Clients](crate::client::Client), so multiple
+//! extrinsics can be submitted concurrently.
+
+use crate::error::Result;
+use sp_core::crypto::AccountId32;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// The managers of all accounts which signed an extrinsic in this process.
+static MANAGERS: OnceLock<Mutex<HashMap<AccountId32, Arc<NonceManager>>>> = OnceLock::new();
+
+/// Hands out nonces for the extrinsics of a single signer. The first nonce is fetched from the
+/// chain, after which nonces are incremented locally, so extrinsics which are still in the
+/// transaction pool don't cause collisions. If a submission fails, the manager resyncs with the
+/// chain on the next request for a nonce.
+#[derive(Debug, Default)]
+pub(crate) struct NonceManager {
+    next: Mutex<Option<u32>>,
+}
+
+impl NonceManager {
+    /// The manager of the given account. Every client signing for the account shares it, so
+    /// clients used concurrently never hand out the same nonce.
+    pub(crate) fn for_account(account: &AccountId32) -> Arc<NonceManager> {
+        MANAGERS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap()
+            .entry(account.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Reserve the next nonce. If the next nonce is not known, it is fetched with `fetch`.
+    pub(crate) fn reserve<F>(&self, fetch: F) -> Result<u32>
+    where
+        F: FnOnce() -> Result<u32>,
+    {
+        let mut next = self.next.lock().unwrap();
+        let nonce = match *next {
+            Some(nonce) => nonce,
+            None => fetch()?,
+        };
+        *next = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Forget the locally tracked nonce, so the next nonce is fetched from the chain again.
+    pub(crate) fn resync(&self) {
+        *self.next.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::cell::Cell;
+    use std::thread;
+
+    #[test]
+    fn reserve_fetches_the_first_nonce_only() {
+        let manager = NonceManager::default();
+        let fetched = Cell::new(0);
+        let fetch = || {
+            fetched.set(fetched.get() + 1);
+            Ok(5)
+        };
+        assert_eq!(manager.reserve(fetch).unwrap(), 5);
+        assert_eq!(manager.reserve(fetch).unwrap(), 6);
+        assert_eq!(manager.reserve(fetch).unwrap(), 7);
+        assert_eq!(fetched.get(), 1);
+    }
+
+    #[test]
+    fn failed_fetch_is_tried_again() {
+        let manager = NonceManager::default();
+        assert!(manager
+            .reserve(|| Err(Error::NotFound("next index".into())))
+            .is_err());
+        assert_eq!(manager.reserve(|| Ok(3)).unwrap(), 3);
+    }
+
+    #[test]
+    fn resync_fetches_the_next_nonce_again() {
+        let manager = NonceManager::default();
+        assert_eq!(manager.reserve(|| Ok(5)).unwrap(), 5);
+        assert_eq!(manager.reserve(|| Ok(5)).unwrap(), 6);
+
+        manager.resync();
+
+        assert_eq!(manager.reserve(|| Ok(6)).unwrap(), 6);
+        assert_eq!(manager.reserve(|| Ok(0)).unwrap(), 7);
+    }
+
+    #[test]
+    fn concurrent_reserves_get_unique_nonces() {
+        let manager = Arc::new(NonceManager::default());
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = manager.clone();
+                thread::spawn(move || {
+                    (0..100)
+                        .map(|_| manager.reserve(|| Ok(0)).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut nonces: Vec<_> = threads
+            .into_iter()
+            .flat_map(|t| t.join().unwrap())
+            .collect();
+        nonces.sort_unstable();
+        assert_eq!(nonces, (0..800).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn managers_are_shared_per_account() {
+        let first = AccountId32::new([0xf1; 32]);
+        let second = AccountId32::new([0xf2; 32]);
+        let manager = NonceManager::for_account(&first);
+        assert!(Arc::ptr_eq(&manager, &NonceManager::for_account(&first)));
+        assert!(!Arc::ptr_eq(&manager, &NonceManager::for_account(&second)));
+
+        assert_eq!(manager.reserve(|| Ok(10)).unwrap(), 10);
+        assert_eq!(
+            NonceManager::for_account(&first).reserve(|| Ok(0)).unwrap(),
+            11
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! Output formatting of the tfchain_cli binary. Every command produces a serializable value,
+//! which is printed in the format selected with the global `--output` flag.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::Cell;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The names of the output formats, as accepted by `--output`.
+pub const FORMATS: [&str; 4] = ["json", "yaml", "table", "text"];
+
+/// The format in which command output is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Pretty printed JSON. Streaming commands print one compact JSON value per line (NDJSON).
+    Json,
+    /// YAML. Streaming commands print every item as a separate YAML document.
+    Yaml,
+    /// Aligned columns. Lists of objects are printed with a column per field, single objects
+    /// with a row per field. Nested objects are flattened into dotted field names.
+    Table,
+    /// Human readable text.
+    Text,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "table" => Ok(Format::Table),
+            "text" => Ok(Format::Text),
+            _ => Err(format!(
+                "unknown output format {}, expected one of {}",
+                s,
+                FORMATS.join(", ")
+            )),
+        }
+    }
+}
+
+impl Format {
+    /// Render the result of a command, as printed by [Output::value].
+    fn render<T, F>(self, value: &T, text: F) -> Result<String, String>
+    where
+        T: Serialize,
+        F: FnOnce() -> String,
+    {
+        match self {
+            Format::Text => Ok(format!("{}\n", text())),
+            Format::Json => lines(serde_json::to_string_pretty(value)),
+            Format::Yaml => lines(serde_yaml::to_string(value)),
+            Format::Table => serde_json::to_value(value)
+                .map(|value| table(&value))
+                .map_err(serialize_error),
+        }
+    }
+
+    /// Render a single item of a streaming command, as printed by [Output::item].
+    fn render_item<T, F>(self, value: &T, text: F) -> Result<String, String>
+    where
+        T: Serialize,
+        F: FnOnce() -> String,
+    {
+        match self {
+            Format::Json => lines(serde_json::to_string(value)),
+            // Every document already starts with a `---` marker.
+            Format::Yaml => lines(serde_yaml::to_string(value)),
+            Format::Table => Ok(self.render(value, text)? + "\n"),
+            Format::Text => self.render(value, text),
+        }
+    }
+}
+
+/// Prints command output in the selected [Format]. Errors are always printed as text on stderr,
+/// and are remembered so the process can exit with a failure code.
+pub struct Output {
+    format: Format,
+    failed: Cell<bool>,
+}
+
+impl Output {
+    pub fn new(format: Format) -> Self {
+        Output {
+            format,
+            failed: Cell::new(false),
+        }
+    }
+
+    /// Print the result of a command. In [Format::Text], `text` is printed instead of the value.
+    pub fn value<T, F>(&self, value: &T, text: F)
+    where
+        T: Serialize,
+        F: FnOnce() -> String,
+    {
+        self.print(self.format.render(value, text))
+    }
+
+    /// Print a single item of a streaming command, as soon as it is available. In [Format::Json]
+    /// every item is printed on a single line, so the output is valid NDJSON.
+    pub fn item<T, F>(&self, value: &T, text: F)
+    where
+        T: Serialize,
+        F: FnOnce() -> String,
+    {
+        self.print(self.format.render_item(value, text))
+    }
+
+    /// Print an error on stderr, and mark the command as failed.
+    pub fn error<M>(&self, msg: M)
+    where
+        M: Display,
+    {
+        eprintln!("{}", msg);
+        self.failed.set(true);
+    }
+
+    /// Indicates if an error was printed.
+    pub fn failed(&self) -> bool {
+        self.failed.get()
+    }
+
+    fn print(&self, res: Result<String, String>) {
+        match res {
+            Ok(s) => print!("{}", s),
+            Err(e) => self.error(e),
+        }
+    }
+}
+
+/// End serialized output with a single newline.
+fn lines<E>(res: Result<String, E>) -> Result<String, String>
+where
+    E: Display,
+{
+    res.map(|s| format!("{}\n", s.trim_end()))
+        .map_err(serialize_error)
+}
+
+fn serialize_error<E>(e: E) -> String
+where
+    E: Display,
+{
+    format!("could not serialize output: {}", e)
+}
+
+/// Render a value as a table. Lists of objects get a column per field, other values a row per
+/// field.
+fn table(value: &Value) -> String {
+    match value {
+        Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+            let rows: Vec<Vec<(String, String)>> = items.iter().map(flatten).collect();
+            let mut header: Vec<String> = Vec::new();
+            for (key, _) in rows.iter().flatten() {
+                if !header.contains(key) {
+                    header.push(key.clone());
+                }
+            }
+            let cells = rows
+                .iter()
+                .map(|row| {
+                    header
+                        .iter()
+                        .map(|key| {
+                            row.iter()
+                                .find(|(k, _)| k == key)
+                                .map(|(_, v)| v.clone())
+                                .unwrap_or_default()
+                        })
+                        .collect()
+                })
+                .collect::<Vec<Vec<String>>>();
+            align(&header, &cells)
+        }
+        Value::Object(_) => {
+            let rows = flatten(value)
+                .into_iter()
+                .map(|(key, value)| vec![key, value])
+                .collect::<Vec<_>>();
+            align(&["FIELD".to_string(), "VALUE".to_string()], &rows)
+        }
+        value => format!("{}\n", cell(value)),
+    }
+}
+
+/// Flatten an object into (dotted key, cell) pairs. Nested objects are flattened recursively,
+/// other values are rendered as a single cell.
+fn flatten(value: &Value) -> Vec<(String, String)> {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+        match value {
+            Value::Object(fields) if !fields.is_empty() => {
+                for (key, value) in fields {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&key, value, out);
+                }
+            }
+            value => out.push((prefix.to_string(), cell(value))),
+        }
+    }
+
+    let mut out = Vec::new();
+    walk("", value, &mut out);
+    out
+}
+
+/// Render a single value for a table cell. Strings are printed without quotes, lists and
+/// objects as compact JSON.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Align the header and rows in columns, separated by 2 spaces.
+fn align(header: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    for row in std::iter::once(header).chain(rows.iter().map(Vec::as_slice)) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Farm {
+        id: u32,
+        name: String,
+    }
+
+    fn farm(id: u32, name: &str) -> Farm {
+        Farm {
+            id,
+            name: name.to_string(),
+        }
+    }
+
+    fn text() -> String {
+        "farm 1".to_string()
+    }
+
+    #[test]
+    fn formats_are_parsed() {
+        for name in FORMATS {
+            assert!(name.parse::<Format>().is_ok(), "{}", name);
+        }
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn json_is_pretty_printed() {
+        assert_eq!(
+            Format::Json.render(&farm(1, "farm"), text).unwrap(),
+            "{\n  \"id\": 1,\n  \"name\": \"farm\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn json_items_are_printed_on_a_single_line() {
+        for (id, name) in [(1, "farm"), (2, "other")] {
+            assert_eq!(
+                Format::Json.render_item(&farm(id, name), text).unwrap(),
+                format!("{{\"id\":{},\"name\":\"{}\"}}\n", id, name)
+            );
+        }
+    }
+
+    #[test]
+    fn yaml_values_and_items_are_documents() {
+        let expected = "---\nid: 1\nname: farm\n";
+        assert_eq!(
+            Format::Yaml.render(&farm(1, "farm"), text).unwrap(),
+            expected
+        );
+        assert_eq!(
+            Format::Yaml.render_item(&farm(1, "farm"), text).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn table_of_a_single_object_has_a_row_per_field() {
+        assert_eq!(
+            Format::Table.render(&farm(1, "farm"), text).unwrap(),
+            "FIELD  VALUE\nid     1\nname   farm\n"
+        );
+    }
+
+    #[test]
+    fn table_of_a_list_has_a_column_per_field() {
+        let farms = vec![farm(1, "farm"), farm(2, "other")];
+        assert_eq!(
+            Format::Table.render(&farms, text).unwrap(),
+            "id  name\n1   farm\n2   other\n"
+        );
+    }
+
+    #[test]
+    fn table_flattens_nested_objects() {
+        let mut nested = BTreeMap::new();
+        nested.insert("farm", farm(1, "farm"));
+        assert_eq!(
+            Format::Table.render(&nested, text).unwrap(),
+            "FIELD      VALUE\nfarm.id    1\nfarm.name  farm\n"
+        );
+    }
+
+    #[test]
+    fn table_items_are_separated_by_a_blank_line() {
+        assert_eq!(
+            Format::Table.render_item(&farm(1, "farm"), text).unwrap(),
+            "FIELD  VALUE\nid     1\nname   farm\n\n"
+        );
+    }
+
+    #[test]
+    fn text_uses_the_given_text() {
+        assert_eq!(
+            Format::Text.render(&farm(1, "farm"), text).unwrap(),
+            "farm 1\n"
+        );
+        assert_eq!(
+            Format::Text.render_item(&farm(1, "farm"), text).unwrap(),
+            "farm 1\n"
+        );
+    }
+
+    #[test]
+    fn serialization_errors_fail_the_command() {
+        // JSON objects only have string keys.
+        let mut invalid = BTreeMap::new();
+        invalid.insert((1, 2), 3);
+        for format in [Format::Json, Format::Table] {
+            let err = format.render(&invalid, text).unwrap_err();
+            assert!(err.starts_with("could not serialize output"), "{}", err);
+        }
+
+        let out = Output::new(Format::Json);
+        assert!(!out.failed());
+        out.value(&invalid, text);
+        assert!(out.failed());
+    }
+
+    #[test]
+    fn errors_fail_the_command() {
+        let out = Output::new(Format::Text);
+        out.value(&farm(1, "farm"), text);
+        assert!(!out.failed());
+        out.error("could not get farm");
+        assert!(out.failed());
+    }
+}
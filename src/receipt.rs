@@ -0,0 +1,53 @@
+//! The result of submitting an extrinsic with
+//! [submit_and_watch](crate::client::Client::submit_and_watch).
+
+use crate::events::TfchainEvent;
+use crate::types::{BlockNumber, DispatchError, Hash};
+
+/// The point in the lifecycle of an extrinsic up to which it is watched. Extrinsics always pass
+/// through the `Ready` state in the transaction pool first. If they are dropped from the pool,
+/// or found to be invalid, the submission fails with an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TxStatus {
+    /// The extrinsic is included in a block, which might still be retracted.
+    InBlock,
+    /// The block including the extrinsic is finalized.
+    Finalized,
+}
+
+/// Details about an extrinsic which was included in a block.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipt {
+    /// The status of the extrinsic when the receipt was created.
+    pub status: TxStatus,
+    /// The hash of the block which included the extrinsic.
+    pub block: Hash,
+    /// The height of the block which included the extrinsic.
+    pub height: BlockNumber,
+    /// The index of the extrinsic in the block.
+    pub extrinsic_index: u32,
+    /// The events emitted while applying the extrinsic.
+    pub events: Vec<TfchainEvent>,
+    /// Indicates if the extrinsic was applied successfully.
+    pub outcome: Outcome,
+}
+
+impl Receipt {
+    /// Check if the extrinsic was applied successfully.
+    pub fn is_success(&self) -> bool {
+        self.outcome == Outcome::Success
+    }
+}
+
+/// The result of applying an extrinsic. Note that a failed extrinsic is still included in a
+/// block, and fees are still paid for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Outcome {
+    /// The extrinsic emitted `ExtrinsicSuccess`.
+    Success,
+    /// The extrinsic emitted `ExtrinsicFailed`, with the given error.
+    Failed(DispatchError),
+}
@@ -0,0 +1,300 @@
+//! Configuration of how a [Client](crate::client::Client) retries failed calls to the chain.
+
+use crate::error::Error;
+use rand::Rng;
+use std::time::{Duration, Instant};
+use substrate_api_client::ApiClientError;
+
+/// Classification of the errors returned by calls to the chain, used to decide which errors are
+/// worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The connection to the node was lost while waiting for a response.
+    Disconnected,
+    /// The websocket connection could not be established or broke down.
+    Connection,
+    /// The node returned an error for the rpc call.
+    Rpc,
+    /// A value returned by the node could not be decoded.
+    Decode,
+    /// An extrinsic was submitted, but its outcome is unknown. Errors of this kind are never
+    /// retried, as that could apply the extrinsic twice.
+    Submitted,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Classify an [Error].
+    pub fn of(err: &Error) -> ErrorKind {
+        match err {
+            Error::Transport(ApiClientError::Disconnected(_)) => ErrorKind::Disconnected,
+            Error::Transport(
+                ApiClientError::WebSocket(_)
+                | ApiClientError::Genesis
+                | ApiClientError::RuntimeVersion
+                | ApiClientError::MetadataFetch,
+            ) => ErrorKind::Connection,
+            Error::Transport(ApiClientError::RpcClient(_)) => ErrorKind::Rpc,
+            Error::Decode(_) | Error::RuntimeMismatch(_) => ErrorKind::Decode,
+            Error::StatusUnknown { .. } => ErrorKind::Submitted,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// A `RetryPolicy` decides how often, and how fast, a failed call is retried. Delays between
+/// attempts grow exponentially, with random jitter applied to avoid many clients hammering a
+/// recovering node at the same time.
+///
+/// Before every retry the connection to the node is reestablished.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: u32,
+    jitter: bool,
+    deadline: Option<Duration>,
+    retryable: Vec<ErrorKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 6,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2,
+            jitter: true,
+            deadline: None,
+            retryable: vec![ErrorKind::Disconnected, ErrorKind::Connection],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub fn never() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Set the maximum amount of attempts made for a single call, including the first one. Values
+    /// lower than 1 are treated as 1.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry, and the maximum delay between any 2 attempts.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Set the factor by which the delay grows after every failed attempt.
+    pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier.max(1);
+        self
+    }
+
+    /// Enable or disable random jitter on the delay between attempts. If enabled, the actual delay
+    /// is picked uniformly between half the computed delay and the full computed delay.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the total amount of time a call, including all retries, can take. No new attempt is
+    /// started if it would begin after the deadline.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Set the kinds of errors which are retried. Errors of any other kind are returned
+    /// immediately. [ErrorKind::Submitted] is never retried, even if it is included.
+    pub fn with_retryable(mut self, kinds: &[ErrorKind]) -> Self {
+        self.retryable = kinds.to_vec();
+        self
+    }
+
+    /// Check if an error of the given kind should be retried.
+    pub fn is_retryable(&self, kind: ErrorKind) -> bool {
+        kind != ErrorKind::Submitted && self.retryable.contains(&kind)
+    }
+
+    /// Start tracking a new call under this policy.
+    pub(crate) fn start(&self) -> Attempts<'_> {
+        Attempts {
+            policy: self,
+            started: Instant::now(),
+            attempt: 1,
+        }
+    }
+
+    /// The delay before the given retry, without jitter applied. Retries are counted from 1.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.multiplier
+            .checked_pow(retry - 1)
+            .and_then(|factor| self.initial_backoff.checked_mul(factor))
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+/// Tracks the attempts made for a single call.
+pub(crate) struct Attempts<'a> {
+    policy: &'a RetryPolicy,
+    started: Instant,
+    attempt: u32,
+}
+
+impl<'a> Attempts<'a> {
+    /// Decide if a call which failed with the given error kind should be attempted again. If so,
+    /// the delay to wait before doing so is returned.
+    pub(crate) fn next_delay(&mut self, kind: ErrorKind) -> Option<Duration> {
+        if !self.policy.is_retryable(kind) || self.attempt >= self.policy.max_attempts {
+            return None;
+        }
+
+        let mut delay = self.policy.backoff(self.attempt);
+        if self.policy.jitter && !delay.is_zero() {
+            delay = rand::thread_rng().gen_range(delay / 2..=delay);
+        }
+
+        if let Some(deadline) = self.policy.deadline {
+            if self.started.elapsed() + delay >= deadline {
+                return None;
+            }
+        }
+
+        self.attempt += 1;
+        Some(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .with_jitter(false)
+    }
+
+    #[test]
+    fn backoff_grows_exponentially() {
+        let policy = policy();
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = policy();
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        // The factor overflows here, which must not panic.
+        assert_eq!(policy.backoff(100), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn backoff_with_multiplier() {
+        let policy = policy().with_multiplier(3);
+        assert_eq!(policy.backoff(2), Duration::from_millis(300));
+        assert_eq!(policy.backoff(3), Duration::from_millis(900));
+    }
+
+    #[test]
+    fn attempts_are_limited() {
+        let policy = policy().with_max_attempts(3);
+        let mut attempts = policy.start();
+        assert_eq!(
+            attempts.next_delay(ErrorKind::Disconnected),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            attempts.next_delay(ErrorKind::Connection),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(attempts.next_delay(ErrorKind::Disconnected), None);
+    }
+
+    #[test]
+    fn never_retries() {
+        let policy = RetryPolicy::never();
+        assert_eq!(policy.start().next_delay(ErrorKind::Disconnected), None);
+    }
+
+    #[test]
+    fn only_retryable_kinds_are_retried() {
+        let policy = policy();
+        assert_eq!(policy.start().next_delay(ErrorKind::Decode), None);
+        assert_eq!(policy.start().next_delay(ErrorKind::Rpc), None);
+
+        let policy = policy.with_retryable(&[ErrorKind::Rpc]);
+        assert!(policy.start().next_delay(ErrorKind::Rpc).is_some());
+        assert_eq!(policy.start().next_delay(ErrorKind::Disconnected), None);
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let policy = policy().with_jitter(true).with_max_attempts(100);
+        let mut attempts = policy.start();
+        for retry in 1..100 {
+            let max = policy.backoff(retry);
+            let delay = attempts.next_delay(ErrorKind::Disconnected).unwrap();
+            assert!(
+                delay >= max / 2 && delay <= max,
+                "{:?} outside of {:?}",
+                delay,
+                max
+            );
+        }
+    }
+
+    #[test]
+    fn deadline_stops_retries() {
+        let policy = policy().with_deadline(Duration::from_millis(150));
+        let mut attempts = policy.start();
+        assert!(attempts.next_delay(ErrorKind::Disconnected).is_some());
+        // Waiting 200ms for the next attempt would pass the deadline.
+        assert_eq!(attempts.next_delay(ErrorKind::Disconnected), None);
+    }
+
+    #[test]
+    fn classifies_errors() {
+        assert_eq!(
+            ErrorKind::of(&Error::Decode("bad".into())),
+            ErrorKind::Decode
+        );
+        assert_eq!(
+            ErrorKind::of(&Error::RuntimeMismatch("bad".into())),
+            ErrorKind::Decode
+        );
+        assert_eq!(
+            ErrorKind::of(&Error::InvalidInput("bad".into())),
+            ErrorKind::Other
+        );
+        assert_eq!(
+            ErrorKind::of(&Error::StatusUnknown {
+                extrinsic: Default::default(),
+                reason: "disconnected".into(),
+            }),
+            ErrorKind::Submitted
+        );
+    }
+
+    #[test]
+    fn submitted_extrinsics_are_never_retried() {
+        let policy = policy().with_retryable(&[ErrorKind::Submitted, ErrorKind::Other]);
+        assert!(!policy.is_retryable(ErrorKind::Submitted));
+        assert_eq!(policy.start().next_delay(ErrorKind::Submitted), None);
+    }
+}
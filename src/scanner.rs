@@ -0,0 +1,452 @@
+//! Scanning of a range of historic blocks, see [RangeScanner].
+
+use crate::error::Error;
+use crate::filter::EventFilter;
+use crate::types::BlockNumber;
+use crate::window::{BlockLoader, EventTypedClient, FinalizedBlock, WindowError, WindowResult};
+use chrono::prelude::*;
+use sp_core::crypto::Pair;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use substrate_api_client::sp_runtime::MultiSignature;
+
+/// The default amount of workers fetching blocks concurrently.
+const DEFAULT_WORKERS: usize = 8;
+
+/// The amount of blocks every worker can fetch ahead of the block which is returned next. This
+/// bounds the amount of blocks held in memory while waiting for a slow block.
+const BLOCKS_AHEAD_PER_WORKER: u32 = 4;
+
+/// The default amount of times a block which failed to load is tried again.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// The progress of a scan. Persisting a checkpoint and passing it to [RangeScanner::resume]
+/// continues a scan where it was stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    /// The height of the next block to return.
+    pub next: BlockNumber,
+    /// The height of the last block in the range.
+    pub end: BlockNumber,
+}
+
+impl Checkpoint {
+    /// Indicates if all blocks in the range have been returned.
+    pub fn is_done(&self) -> bool {
+        self.next > self.end
+    }
+}
+
+/// A `RangeScanner` loads the timestamp and events of every block in a range of heights. Blocks
+/// are fetched concurrently by a bounded pool of workers, but they are returned in order of their
+/// height. As with a [BlockStream](crate::window::BlockStream), the events of every block are
+/// decoded with the runtime which produced it.
+pub struct RangeScanner<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    client: EventTypedClient<P>,
+    checkpoint: Checkpoint,
+    workers: usize,
+    max_retries: u32,
+    filter: Option<EventFilter>,
+}
+
+impl<P> RangeScanner<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    /// Create a new [RangeScanner] for the blocks in a range of heights.
+    pub fn new<C>(client: C, range: RangeInclusive<BlockNumber>) -> Self
+    where
+        C: Into<EventTypedClient<P>>,
+    {
+        Self::resume(
+            client,
+            Checkpoint {
+                next: *range.start(),
+                end: *range.end(),
+            },
+        )
+    }
+
+    /// Create a new [RangeScanner] for the blocks made between 2 dates. The dates are converted
+    /// to heights with [height_at_timestamp](crate::client::Client::height_at_timestamp).
+    pub fn between<C>(client: C, from: DateTime<Utc>, to: DateTime<Utc>) -> WindowResult<Self>
+    where
+        C: Into<EventTypedClient<P>>,
+    {
+        let client = client.into();
+        let start = client.height_at_timestamp(from.timestamp())?;
+        let end = client.height_at_timestamp(to.timestamp())?;
+        Ok(Self::new(client, start..=end))
+    }
+
+    /// Create a [RangeScanner] which continues a previous scan from a [Checkpoint].
+    pub fn resume<C>(client: C, checkpoint: Checkpoint) -> Self
+    where
+        C: Into<EventTypedClient<P>>,
+    {
+        RangeScanner {
+            client: client.into(),
+            checkpoint,
+            workers: DEFAULT_WORKERS,
+            max_retries: DEFAULT_MAX_RETRIES,
+            filter: None,
+        }
+    }
+
+    /// Set the amount of workers fetching blocks concurrently. At least 1 worker is used.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Set the amount of times a block which failed to load is tried again, before the scan
+    /// stops. See [Scan].
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Only keep the events matching the [filter](EventFilter) in the returned blocks.
+    pub fn with_filter(mut self, filter: EventFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Start the workers, and get an iterator over the blocks in the range.
+    pub fn scan(self) -> Scan {
+        let loader = BlockLoader::new(self.client);
+        let filter = self.filter;
+        Scan::start(
+            self.checkpoint,
+            self.workers,
+            self.max_retries,
+            move |height| load(&loader, height, filter.as_ref()),
+        )
+    }
+}
+
+/// An iterator over the blocks of a [RangeScanner], in order of their height.
+///
+/// If a block can't be loaded, the error is returned in its place. If the error is caused by the
+/// connection to the node, iterating further retries the same block, up to
+/// [RangeScanner::with_max_retries] times. After any other error, or once the retries are used up,
+/// the iterator ends, so no block is ever skipped. A worker which panics is reported as a
+/// [WindowError::WorkerPanicked] for the block it was loading, which also ends the iterator. The
+/// [checkpoint](Scan::checkpoint) then points at the failed block, and can be used to resume the
+/// scan later. The workers are stopped once the iterator is dropped.
+pub struct Scan {
+    checkpoint: Checkpoint,
+    /// The height of the next block to hand to the workers.
+    dispatched: BlockNumber,
+    /// The amount of blocks which can be handed to the workers ahead of the next block.
+    ahead: u32,
+    max_retries: u32,
+    /// The amount of times the next block failed to load.
+    failures: u32,
+    /// Set once a block failed to load and is not retried.
+    stopped: bool,
+    jobs: mpsc::Sender<BlockNumber>,
+    results: mpsc::Receiver<(BlockNumber, WindowResult<FinalizedBlock>)>,
+    /// Blocks loaded by the workers, which can't be returned yet.
+    loaded: BTreeMap<BlockNumber, WindowResult<FinalizedBlock>>,
+}
+
+impl Scan {
+    /// Start the workers, which load blocks with `load`.
+    fn start<F>(checkpoint: Checkpoint, workers: usize, max_retries: u32, load: F) -> Scan
+    where
+        F: Fn(BlockNumber) -> WindowResult<FinalizedBlock> + Send + Sync + 'static,
+    {
+        let (jobs_in, jobs_out) = mpsc::channel();
+        let (results_in, results_out) = mpsc::channel();
+        let jobs_out = Arc::new(Mutex::new(jobs_out));
+        let load = Arc::new(load);
+
+        let range = checkpoint
+            .end
+            .saturating_sub(checkpoint.next)
+            .saturating_add(1) as usize;
+        for _ in 0..workers.min(range) {
+            let load = load.clone();
+            let jobs = jobs_out.clone();
+            let results = results_in.clone();
+            thread::spawn(move || work(&*load, jobs, results));
+        }
+
+        Scan {
+            checkpoint,
+            dispatched: checkpoint.next,
+            ahead: BLOCKS_AHEAD_PER_WORKER * workers as u32,
+            max_retries,
+            failures: 0,
+            stopped: false,
+            jobs: jobs_in,
+            results: results_out,
+            loaded: BTreeMap::new(),
+        }
+    }
+
+    /// The current progress of the scan.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.checkpoint
+    }
+}
+
+impl Iterator for Scan {
+    type Item = WindowResult<FinalizedBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stopped || self.checkpoint.is_done() {
+            return None;
+        }
+
+        loop {
+            let limit = self
+                .checkpoint
+                .end
+                .min(self.checkpoint.next.saturating_add(self.ahead));
+            while self.dispatched <= limit {
+                // Workers only stop if the receiving end is gone.
+                if self.jobs.send(self.dispatched).is_err() {
+                    return None;
+                }
+                self.dispatched += 1;
+            }
+
+            if let Some(res) = self.loaded.remove(&self.checkpoint.next) {
+                match res {
+                    Ok(_) => {
+                        self.checkpoint.next += 1;
+                        self.failures = 0;
+                    }
+                    Err(ref err) => {
+                        // Stop at permanent errors, otherwise retry the block on the next call.
+                        self.failures += 1;
+                        if self.failures > self.max_retries || !err.is_transient() {
+                            self.stopped = true;
+                        } else if self.jobs.send(self.checkpoint.next).is_err() {
+                            return None;
+                        }
+                    }
+                }
+                return Some(res);
+            }
+
+            match self.results.recv() {
+                Ok((height, res)) => {
+                    self.loaded.insert(height, res);
+                }
+                // All workers are gone, which only happens if they panicked.
+                Err(_) => {
+                    self.stopped = true;
+                    return Some(Err(WindowError::WorkerPanicked(self.checkpoint.next)));
+                }
+            }
+        }
+    }
+}
+
+type Results = mpsc::Sender<(BlockNumber, WindowResult<FinalizedBlock>)>;
+
+/// Load the blocks handed out through `jobs` with `load`, until either channel is closed.
+fn work<F>(load: &F, jobs: Arc<Mutex<mpsc::Receiver<BlockNumber>>>, results: Results)
+where
+    F: Fn(BlockNumber) -> WindowResult<FinalizedBlock>,
+{
+    loop {
+        // The lock is released as soon as a job is received.
+        let height = match jobs.lock().unwrap().recv() {
+            Ok(height) => height,
+            Err(_) => return,
+        };
+        let res = {
+            let _guard = PanicGuard {
+                height,
+                results: &results,
+            };
+            load(height)
+        };
+        if results.send((height, res)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Reports a panic of a worker as the result of the block it was loading. Otherwise the [Scan]
+/// would wait for that block forever, as the other workers keep the results channel open.
+struct PanicGuard<'a> {
+    height: BlockNumber,
+    results: &'a Results,
+}
+
+impl Drop for PanicGuard<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            let err = WindowError::WorkerPanicked(self.height);
+            let _ = self.results.send((self.height, Err(err)));
+        }
+    }
+}
+
+/// Load the block at a height.
+fn load<P>(
+    loader: &BlockLoader<P>,
+    height: BlockNumber,
+    filter: Option<&EventFilter>,
+) -> WindowResult<FinalizedBlock>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    let hash = loader
+        .client()
+        .get_hash_at_height(height)?
+        .ok_or_else(|| Error::NotFound(format!("block at height {}", height)))?;
+    loader.load_block(height, hash, filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_api_client::ApiClientError;
+
+    fn block(height: BlockNumber) -> FinalizedBlock {
+        FinalizedBlock {
+            height,
+            hash: Default::default(),
+            timestamp: height as i64 * 6000,
+            events: Vec::new(),
+        }
+    }
+
+    fn transient() -> WindowError {
+        WindowError::Api(Error::Transport(ApiClientError::Genesis))
+    }
+
+    fn permanent() -> WindowError {
+        WindowError::Api(Error::RuntimeMismatch("block events".into()))
+    }
+
+    /// The heights of the returned blocks, with `None` for errors.
+    fn heights(scan: &mut Scan) -> Vec<Option<BlockNumber>> {
+        scan.map(|res| res.ok().map(|block| block.height)).collect()
+    }
+
+    /// A loader which fails the given amount of attempts at loading a block, with the given
+    /// error. Every requested height is recorded.
+    fn failing(
+        height: BlockNumber,
+        failures: usize,
+        err: fn() -> WindowError,
+        requested: Arc<Mutex<Vec<BlockNumber>>>,
+    ) -> impl Fn(BlockNumber) -> WindowResult<FinalizedBlock> + Send + Sync + 'static {
+        move |h| {
+            let mut requested = requested.lock().unwrap();
+            requested.push(h);
+            let attempt = requested.iter().filter(|r| **r == h).count();
+            if h == height && attempt <= failures {
+                return Err(err());
+            }
+            Ok(block(h))
+        }
+    }
+
+    fn checkpoint(next: BlockNumber, end: BlockNumber) -> Checkpoint {
+        Checkpoint { next, end }
+    }
+
+    #[test]
+    fn blocks_are_returned_in_order() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let mut scan = Scan::start(checkpoint(1, 20), 4, 3, failing(0, 0, permanent, requested));
+        assert_eq!(heights(&mut scan), (1..=20).map(Some).collect::<Vec<_>>());
+        assert!(scan.checkpoint().is_done());
+    }
+
+    #[test]
+    fn transient_errors_are_retried() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let load = failing(3, 2, transient, requested.clone());
+        let mut scan = Scan::start(checkpoint(1, 5), 2, 3, load);
+
+        assert_eq!(
+            heights(&mut scan),
+            vec![Some(1), Some(2), None, None, Some(3), Some(4), Some(5)]
+        );
+        assert!(scan.checkpoint().is_done());
+        let attempts = requested
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|h| **h == 3)
+            .count();
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn retries_are_bounded() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let load = failing(3, usize::MAX, transient, requested);
+        let mut scan = Scan::start(checkpoint(1, 5), 2, 2, load);
+
+        assert_eq!(heights(&mut scan), vec![Some(1), Some(2), None, None, None]);
+        assert_eq!(scan.checkpoint(), checkpoint(3, 5));
+    }
+
+    #[test]
+    fn permanent_errors_stop_the_scan() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let load = failing(3, 1, permanent, requested);
+        let mut scan = Scan::start(checkpoint(1, 5), 2, 3, load);
+
+        assert_eq!(heights(&mut scan), vec![Some(1), Some(2), None]);
+        assert!(scan.next().is_none());
+        assert_eq!(scan.checkpoint(), checkpoint(3, 5));
+    }
+
+    #[test]
+    fn scan_resumes_from_checkpoint() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let load = failing(3, 1, permanent, requested);
+        let mut scan = Scan::start(checkpoint(1, 5), 1, 3, load);
+        assert_eq!(heights(&mut scan), vec![Some(1), Some(2), None]);
+        let stopped = scan.checkpoint();
+        drop(scan);
+
+        let resumed_requests = Arc::new(Mutex::new(Vec::new()));
+        let load = failing(0, 0, permanent, resumed_requests.clone());
+        let mut resumed = Scan::start(stopped, 1, 3, load);
+
+        assert_eq!(heights(&mut resumed), vec![Some(3), Some(4), Some(5)]);
+        assert!(resumed.checkpoint().is_done());
+        let mut requested = resumed_requests.lock().unwrap().clone();
+        requested.sort_unstable();
+        assert_eq!(requested, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn panicking_worker_is_reported() {
+        let mut scan = Scan::start(checkpoint(1, 5), 2, 3, |height| {
+            if height == 2 {
+                panic!("worker failed");
+            }
+            Ok(block(height))
+        });
+
+        assert_eq!(scan.next().unwrap().unwrap().height, 1);
+        assert!(matches!(
+            scan.next(),
+            Some(Err(WindowError::WorkerPanicked(2)))
+        ));
+        assert!(scan.next().is_none());
+    }
+}
@@ -0,0 +1,224 @@
+//! Serialization of the types, events and calls of this crate with serde. This module is only
+//! available with the `serde` feature enabled.
+//!
+//! All values use the default serde representation, e.g. structs are objects with the field
+//! names as keys, and enums are externally tagged (`{"FarmDeleted": 1}`, or `"Diy"` for variants
+//! without data). On top of that, the JSON shape follows these rules:
+//!
+//! - Account ids are SS58 encoded strings, with the generic substrate prefix.
+//! - Hashes are `0x` prefixed hex strings.
+//! - [Tft] amounts are strings with the amount in TFT, e.g. `"12.5"`, as the amount of units
+//!   does not fit in a JSON number without losing precision.
+//! - Byte vectors holding text, like names, IPs and Stellar addresses, are strings, see
+//!   [bytes_str]. Other byte vectors, like signatures, are `0x` prefixed hex strings, see
+//!   [bytes_hex].
+//!
+//! [Tft]: crate::types::Tft
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Encode bytes as a `0x` prefixed hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Decode a `0x` prefixed hex string. Returns None if `s` is not a valid hex string.
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    hex::decode(s.strip_prefix("0x")?).ok()
+}
+
+/// (De)serialize a byte vector holding text as a string. Bytes which are not valid UTF-8 are
+/// serialized as a `0x` prefixed hex string instead. Such a string is only decoded as hex if the
+/// decoded bytes are not valid UTF-8.
+///
+/// The encoding is not tagged, so text which itself is a hex string of bytes that are not valid
+/// UTF-8, like `"0xc3"`, does not round-trip: it is deserialized as the decoded bytes rather than
+/// as the text. All other values are restored as they were serialized.
+pub mod bytes_str {
+    use super::*;
+
+    pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => serializer.serialize_str(s),
+            Err(_) => serializer.serialize_str(&to_hex(bytes)),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match from_hex(&s) {
+            Some(bytes) if std::str::from_utf8(&bytes).is_err() => Ok(bytes),
+            _ => Ok(s.into_bytes()),
+        }
+    }
+}
+
+/// (De)serialize a list of byte vectors holding text as a list of strings, see [bytes_str].
+pub mod bytes_str_vec {
+    use super::*;
+
+    struct TextRef<'a>(&'a [u8]);
+
+    impl Serialize for TextRef<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            super::bytes_str::serialize(self.0, serializer)
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Text(#[serde(with = "super::bytes_str")] Vec<u8>);
+
+    pub fn serialize<S>(list: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(list.iter().map(|bytes| TextRef(bytes)))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let list = Vec::<Text>::deserialize(deserializer)?;
+        Ok(list.into_iter().map(|text| text.0).collect())
+    }
+}
+
+/// (De)serialize a byte vector as a `0x` prefixed hex string.
+pub mod bytes_hex {
+    use super::*;
+    use serde::de::Error;
+
+    pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&to_hex(bytes))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        from_hex(&s)
+            .ok_or_else(|| D::Error::custom(format!("{} is not a 0x prefixed hex string", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Tft;
+    use serde_json::json;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Text(#[serde(with = "bytes_str")] Vec<u8>);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TextList(#[serde(with = "bytes_str_vec")] Vec<Vec<u8>>);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Binary(#[serde(with = "bytes_hex")] Vec<u8>);
+
+    /// Serialize a value to JSON, check the JSON, and deserialize it again.
+    fn round_trip<T>(value: &T, expected: serde_json::Value) -> T
+    where
+        T: Serialize + for<'de> Deserialize<'de>,
+    {
+        let json = serde_json::to_value(value).unwrap();
+        assert_eq!(json, expected);
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn utf8_text_is_a_string() {
+        for text in ["farm-1", "Gent, België", ""] {
+            let value = Text(text.as_bytes().to_vec());
+            assert_eq!(round_trip(&value, json!(text)), value);
+        }
+    }
+
+    #[test]
+    fn non_utf8_text_is_hex() {
+        let value = Text(vec![0xc3, 0x28]);
+        assert_eq!(round_trip(&value, json!("0xc328")), value);
+    }
+
+    #[test]
+    fn hex_text_of_utf8_bytes_stays_text() {
+        // "0x6869" decodes to "hi", which is valid UTF-8, so the string is kept as text.
+        let value = Text(b"0x6869".to_vec());
+        assert_eq!(round_trip(&value, json!("0x6869")), value);
+    }
+
+    #[test]
+    fn hex_text_of_non_utf8_bytes_does_not_round_trip() {
+        let json = serde_json::to_value(Text(b"0xc3".to_vec())).unwrap();
+        assert_eq!(
+            serde_json::from_value::<Text>(json).unwrap(),
+            Text(vec![0xc3])
+        );
+    }
+
+    #[test]
+    fn text_list_is_a_list_of_strings() {
+        let value = TextList(vec![b"1.1.1.1".to_vec(), vec![0xff], Vec::new()]);
+        assert_eq!(round_trip(&value, json!(["1.1.1.1", "0xff", ""])), value);
+    }
+
+    #[test]
+    fn binary_is_hex() {
+        let value = Binary(vec![0x00, 0x01, 0xab]);
+        assert_eq!(round_trip(&value, json!("0x0001ab")), value);
+        let value = Binary(b"farm".to_vec());
+        assert_eq!(round_trip(&value, json!("0x6661726d")), value);
+        let value = Binary(Vec::new());
+        assert_eq!(round_trip(&value, json!("0x")), value);
+    }
+
+    #[test]
+    fn binary_requires_prefixed_hex() {
+        for invalid in ["0001ab", "0xzz", "0xabc"] {
+            assert!(
+                serde_json::from_value::<Binary>(json!(invalid)).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn tft_is_a_string_in_tft() {
+        for (units, text) in [
+            (0u128, "0"),
+            (1, "0.0000001"),
+            (125_000_000, "12.5"),
+            (10_000_000, "1"),
+        ] {
+            let amount = Tft::from(units);
+            assert_eq!(round_trip(&amount, json!(text)), amount);
+        }
+    }
+
+    #[test]
+    fn tft_rejects_invalid_amounts() {
+        for invalid in ["", "1.00000001", "-1", "1,5"] {
+            assert!(
+                serde_json::from_value::<Tft>(json!(invalid)).is_err(),
+                "{}",
+                invalid
+            );
+        }
+        assert!(serde_json::from_value::<Tft>(json!(12)).is_err());
+    }
+}
@@ -1,13 +1,16 @@
+use crate::error::Error;
 use chrono::prelude::*;
 use codec::{Decode, Encode};
 pub use sp_application_crypto::ed25519;
 pub use sp_core::crypto::AccountId32;
 pub use sp_core::H256 as Hash;
 use std::fmt::{self, Display};
+use std::str::FromStr;
 pub use substrate_api_client::{AccountData, AccountInfo};
 pub use support::traits::BalanceStatus;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeCertification {
     Diy,
     Certified,
@@ -39,7 +42,17 @@ impl From<pallet_tfgrid_legacy::types::CertificationType> for NodeCertification
     }
 }
 
+impl From<NodeCertification> for tfchain_support::types::NodeCertification {
+    fn from(ct: NodeCertification) -> Self {
+        match ct {
+            NodeCertification::Diy => tfchain_support::types::NodeCertification::Diy,
+            NodeCertification::Certified => tfchain_support::types::NodeCertification::Certified,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FarmingPolicy<BlockNumber> {
     pub version: u32,
     pub id: u32,
@@ -130,6 +143,7 @@ impl From<pallet_tfgrid_legacy::types::FarmingPolicy> for FarmingPolicy<BlockNum
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Policy {
     pub value: u32,
     pub unit: Unit,
@@ -156,6 +170,7 @@ impl From<pallet_tfgrid_legacy::types::Policy> for Policy {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Unit {
     Bytes,
     Kilobytes,
@@ -195,6 +210,7 @@ impl From<pallet_tfgrid_legacy::types::Unit> for Unit {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resources {
     pub hru: u64,
     pub sru: u64,
@@ -203,6 +219,7 @@ pub struct Resources {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContractResources {
     pub contract_id: u64,
     pub used: Resources,
@@ -222,10 +239,19 @@ impl From<pallet_tfgrid_legacy::types::Resources> for Resources {
     }
 }
 
+impl From<Resources> for tfchain_support::types::Resources {
+    fn from(r: Resources) -> Self {
+        let Resources { hru, sru, cru, mru } = r;
+        Self { hru, sru, cru, mru }
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PricingPolicy {
     pub version: u32,
     pub id: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub name: Vec<u8>,
     pub su: Policy,
     pub cu: Policy,
@@ -313,25 +339,301 @@ pub type AuthorityId = ed25519::Public;
 /// The weight of an authority.
 pub type AuthorityWeight = u64;
 
-#[derive(Debug)]
-pub struct Balance(u64);
+/// An amount of TFT, stored as the amount of the smallest unit on chain. TFT has 7 decimals, so
+/// 1 TFT is 10^7 units.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Encode, Decode, Default, Debug, Hash)]
+pub struct Tft(u128);
+
+impl Tft {
+    /// The amount of decimals of TFT.
+    pub const DECIMALS: u32 = 7;
+    /// The amount of units in 1 TFT.
+    pub const UNITS_PER_TFT: u128 = 10u128.pow(Tft::DECIMALS);
+    /// No TFT.
+    pub const ZERO: Tft = Tft(0);
 
-impl From<u64> for Balance {
-    fn from(amount: u64) -> Self {
-        Balance(amount)
+    /// Create an amount from the amount of units.
+    pub const fn from_units(units: u128) -> Self {
+        Tft(units)
+    }
+
+    /// Create an amount from a whole amount of TFT. Returns [None] on overflow.
+    pub fn from_tft(tft: u128) -> Option<Self> {
+        tft.checked_mul(Tft::UNITS_PER_TFT).map(Tft)
     }
-}
 
-impl Balance {
-    /// Get the balance as the amount of units expressed as a u64.
-    pub fn as_u64(self) -> u64 {
+    /// Get the amount of units.
+    pub const fn units(self) -> u128 {
         self.0
     }
+
+    /// Add 2 amounts. Returns [None] on overflow.
+    pub fn checked_add(self, rhs: Tft) -> Option<Tft> {
+        self.0.checked_add(rhs.0).map(Tft)
+    }
+
+    /// Subtract an amount. Returns [None] if `rhs` is larger than `self`.
+    pub fn checked_sub(self, rhs: Tft) -> Option<Tft> {
+        self.0.checked_sub(rhs.0).map(Tft)
+    }
+
+    /// Multiply by a factor. Returns [None] on overflow.
+    pub fn checked_mul(self, rhs: u128) -> Option<Tft> {
+        self.0.checked_mul(rhs).map(Tft)
+    }
+
+    /// Divide by a divisor, rounding down. Returns [None] if `rhs` is 0.
+    pub fn checked_div(self, rhs: u128) -> Option<Tft> {
+        self.0.checked_div(rhs).map(Tft)
+    }
+
+    /// Subtract an amount, returning 0 if `rhs` is larger than `self`.
+    pub fn saturating_sub(self, rhs: Tft) -> Tft {
+        Tft(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl From<u128> for Tft {
+    fn from(units: u128) -> Self {
+        Tft(units)
+    }
+}
+
+impl From<u64> for Tft {
+    fn from(units: u64) -> Self {
+        Tft(units.into())
+    }
+}
+
+impl From<Tft> for u128 {
+    fn from(amount: Tft) -> Self {
+        amount.0
+    }
+}
+
+impl Display for Tft {
+    /// Formats the amount in TFT, without trailing zeros in the decimals, e.g. `12.5`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.0 / Tft::UNITS_PER_TFT;
+        let fraction = self.0 % Tft::UNITS_PER_TFT;
+        if fraction == 0 {
+            return write!(f, "{}", whole);
+        }
+        let decimals = format!("{:0width$}", fraction, width = Tft::DECIMALS as usize);
+        write!(f, "{}.{}", whole, decimals.trim_end_matches('0'))
+    }
+}
+
+impl FromStr for Tft {
+    type Err = Error;
+
+    /// Parses an amount in TFT, with at most 7 decimals, e.g. `12.5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |reason: &str| Error::InvalidInput(format!("invalid TFT amount {}: {}", s, reason));
+        let (whole, decimals) = s.split_once('.').unwrap_or((s, ""));
+        if whole.is_empty() && decimals.is_empty() {
+            return Err(invalid("no digits"));
+        }
+        if !whole
+            .chars()
+            .chain(decimals.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid("only digits and a single '.' are allowed"));
+        }
+        if decimals.len() > Tft::DECIMALS as usize {
+            return Err(invalid("more than 7 decimals"));
+        }
+        let whole: u128 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| invalid("too large"))?
+        };
+        let fraction: u128 = if decimals.is_empty() {
+            0
+        } else {
+            decimals.parse::<u128>().map_err(|_| invalid("too large"))?
+                * 10u128.pow(Tft::DECIMALS - decimals.len() as u32)
+        };
+        Tft::from_tft(whole)
+            .and_then(|tft| tft.checked_add(Tft(fraction)))
+            .ok_or_else(|| invalid("too large"))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tft {
+    /// Serializes the amount as a string in TFT, see [Display].
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Tft {
+    /// Deserializes an amount from a string in TFT, see [FromStr].
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let amount = <String as serde::Deserialize>::deserialize(deserializer)?;
+        amount.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Helpers to get the balances in an [AccountData] as [Tft].
+pub trait AccountDataExt {
+    /// The free balance, including the frozen part.
+    fn free_tft(&self) -> Tft;
+    /// The reserved balance.
+    fn reserved_tft(&self) -> Tft;
+    /// The part of the free balance which can't be used.
+    fn frozen_tft(&self) -> Tft;
+    /// The part of the free balance which can be transferred.
+    fn transferable_tft(&self) -> Tft;
+}
+
+impl AccountDataExt for AccountData {
+    fn free_tft(&self) -> Tft {
+        Tft(self.free)
+    }
+
+    fn reserved_tft(&self) -> Tft {
+        Tft(self.reserved)
+    }
+
+    fn frozen_tft(&self) -> Tft {
+        Tft(self.misc_frozen.max(self.fee_frozen))
+    }
+
+    fn transferable_tft(&self) -> Tft {
+        self.free_tft().saturating_sub(self.frozen_tft())
+    }
+}
+
+/// Information about the dispatch of an extrinsic.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DispatchInfo {
+    /// The weight of the extrinsic.
+    pub weight: u64,
+    /// The class of the extrinsic.
+    pub class: DispatchClass,
+    /// Indicates if the sender paid a fee for the extrinsic.
+    pub pays_fee: bool,
+}
+
+impl From<support::weights::DispatchInfo> for DispatchInfo {
+    fn from(di: support::weights::DispatchInfo) -> Self {
+        let support::weights::DispatchInfo {
+            weight,
+            class,
+            pays_fee,
+        } = di;
+        Self {
+            weight,
+            class: class.into(),
+            pays_fee: pays_fee == support::weights::Pays::Yes,
+        }
+    }
+}
+
+/// The class of an extrinsic, which determines the part of the block weight it can use.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DispatchClass {
+    Normal,
+    Operational,
+    Mandatory,
+}
+
+impl From<support::weights::DispatchClass> for DispatchClass {
+    fn from(dc: support::weights::DispatchClass) -> Self {
+        match dc {
+            support::weights::DispatchClass::Normal => DispatchClass::Normal,
+            support::weights::DispatchClass::Operational => DispatchClass::Operational,
+            support::weights::DispatchClass::Mandatory => DispatchClass::Mandatory,
+        }
+    }
+}
+
+/// The reason an extrinsic failed.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DispatchError {
+    /// The extrinsic was rejected by a pallet.
+    Module(ModuleError),
+    /// The origin of the extrinsic is not allowed to call it.
+    BadOrigin,
+    /// An account could not be looked up.
+    CannotLookup,
+    /// Any other error, in its debug representation.
+    Other(String),
+}
+
+impl From<sp_runtime::DispatchError> for DispatchError {
+    fn from(de: sp_runtime::DispatchError) -> Self {
+        match de {
+            sp_runtime::DispatchError::Module { index, error, .. } => {
+                DispatchError::Module(ModuleError {
+                    pallet_index: index,
+                    error_index: error,
+                    pallet: None,
+                    error: None,
+                })
+            }
+            sp_runtime::DispatchError::BadOrigin => DispatchError::BadOrigin,
+            sp_runtime::DispatchError::CannotLookup => DispatchError::CannotLookup,
+            de => DispatchError::Other(format!("{:?}", de)),
+        }
+    }
+}
+
+impl Display for DispatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DispatchError::Module(me) => write!(f, "{}", me),
+            DispatchError::BadOrigin => write!(f, "bad origin"),
+            DispatchError::CannotLookup => write!(f, "cannot lookup"),
+            DispatchError::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// An error returned by a pallet. The names of the pallet and error are only set if they could
+/// be resolved from the runtime metadata, which requires the block to be produced by the runtime
+/// version the client is connected to.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleError {
+    pub pallet_index: u8,
+    pub error_index: u8,
+    pub pallet: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.pallet, &self.error) {
+            (Some(pallet), Some(error)) => write!(f, "{}::{}", pallet, error),
+            (Some(pallet), None) => write!(f, "{}::<error {}>", pallet, self.error_index),
+            _ => write!(
+                f,
+                "<pallet {}>::<error {}>",
+                self.pallet_index, self.error_index
+            ),
+        }
+    }
 }
 
 pub type BlockNumber = u32;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Twin {
     pub version: u32,
     pub id: u32,
@@ -379,12 +681,16 @@ impl From<pallet_tfgrid_legacy::types::Twin<AccountId32>> for Twin {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Encode, Decode, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entity {
     pub version: u32,
     pub id: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub name: Vec<u8>,
     pub account_id: AccountId32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub country: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub city: Vec<u8>,
 }
 
@@ -431,8 +737,10 @@ impl From<pallet_tfgrid_legacy::types::Entity<AccountId32>> for Entity {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityProof {
     pub entity_id: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_hex"))]
     pub signature: Vec<u8>,
 }
 
@@ -463,6 +771,7 @@ impl From<pallet_tfgrid_legacy::types::EntityProof> for EntityProof {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Farm {
     pub version: u32,
     pub id: u32,
@@ -476,6 +785,7 @@ pub struct Farm {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FarmCertification {
     NotCertified,
     Gold,
@@ -499,6 +809,7 @@ impl From<tfchain_support::types::FarmCertification> for FarmCertification {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FarmingPolicyLimit {
     pub farming_policy_id: u32,
     pub cu: Option<u64>,
@@ -590,6 +901,7 @@ impl From<pallet_tfgrid_legacy::types::Farm> for Farm {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PublicIP {
     pub ip: String,
     pub gateway: String,
@@ -627,6 +939,7 @@ impl From<pallet_tfgrid_legacy::types::PublicIP> for PublicIP {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     pub version: u32,
     pub id: u32,
@@ -733,7 +1046,54 @@ impl From<pallet_tfgrid_legacy::types::Node> for Node {
     }
 }
 
+impl From<Node> for tfchain_support::types::Node {
+    fn from(n: Node) -> Self {
+        let Node {
+            version,
+            id,
+            farm_id,
+            twin_id,
+            resources,
+            location,
+            country,
+            city,
+            public_config,
+            created,
+            farming_policy_id,
+            interfaces,
+            certification,
+            secure_boot,
+            virtualized,
+            serial_number,
+            connection_price,
+        } = n;
+        Self {
+            version,
+            id,
+            farm_id,
+            twin_id,
+            resources: resources.into(),
+            location: location.into(),
+            country: country.into_bytes(),
+            city: city.into_bytes(),
+            public_config: public_config.map(tfchain_support::types::PublicConfig::from),
+            created,
+            farming_policy_id,
+            interfaces: interfaces
+                .into_iter()
+                .map(tfchain_support::types::Interface::from)
+                .collect(),
+            certification: certification.into(),
+            secure_boot,
+            virtualized,
+            serial_number: serial_number.into_bytes(),
+            connection_price,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Location {
     pub longitude: String,
     pub latitude: String,
@@ -765,7 +1125,21 @@ impl From<pallet_tfgrid_legacy::types::Location> for Location {
     }
 }
 
+impl From<Location> for tfchain_support::types::Location {
+    fn from(l: Location) -> Self {
+        let Location {
+            longitude,
+            latitude,
+        } = l;
+        Self {
+            longitude: longitude.into_bytes(),
+            latitude: latitude.into_bytes(),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Interface {
     pub name: String,
     pub mac: String,
@@ -800,9 +1174,21 @@ impl From<pallet_tfgrid_legacy::types::Interface> for Interface {
     }
 }
 
+impl From<Interface> for tfchain_support::types::Interface {
+    fn from(iface: Interface) -> Self {
+        let Interface { name, mac, ips } = iface;
+        Self {
+            name: name.into_bytes(),
+            mac: mac.into_bytes(),
+            ips: ips.into_iter().map(String::into_bytes).collect(),
+        }
+    }
+}
+
 pub type IP = String;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PublicConfig {
     pub ipv4: String,
     pub ipv6: String,
@@ -849,7 +1235,27 @@ impl From<pallet_tfgrid_legacy::types::PublicConfig> for PublicConfig {
     }
 }
 
+impl From<PublicConfig> for tfchain_support::types::PublicConfig {
+    fn from(pc: PublicConfig) -> Self {
+        let PublicConfig {
+            ipv4,
+            ipv6,
+            gw4,
+            gw6,
+            domain,
+        } = pc;
+        Self {
+            ipv4: ipv4.into_bytes(),
+            ipv6: ipv6.into_bytes(),
+            gw4: gw4.into_bytes(),
+            gw6: gw6.into_bytes(),
+            domain: domain.into_bytes(),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DiscountLevel {
     None,
     Default,
@@ -902,6 +1308,7 @@ impl From<pallet_smart_contract_legacy::types::DiscountLevel> for DiscountLevel
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Consumption {
     pub contract_id: u64,
     pub timestamp: u64,
@@ -958,7 +1365,75 @@ impl From<pallet_smart_contract_legacy::types::Consumption> for Consumption {
     }
 }
 
+impl From<Consumption> for pallet_smart_contract::types::Consumption {
+    fn from(c: Consumption) -> Self {
+        let Consumption {
+            contract_id,
+            timestamp,
+            cru,
+            sru,
+            hru,
+            mru,
+            nru,
+        } = c;
+        Self {
+            contract_id,
+            timestamp,
+            cru,
+            sru,
+            hru,
+            mru,
+            nru,
+        }
+    }
+}
+
+/// Public network traffic used by a contract during a window of time ending at the timestamp.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NruConsumption {
+    pub contract_id: u64,
+    pub timestamp: u64,
+    pub window: u64,
+    pub nru: u64,
+}
+
+impl From<pallet_smart_contract::types::NruConsumption> for NruConsumption {
+    fn from(c: pallet_smart_contract::types::NruConsumption) -> Self {
+        let pallet_smart_contract::types::NruConsumption {
+            contract_id,
+            timestamp,
+            window,
+            nru,
+        } = c;
+        Self {
+            contract_id,
+            timestamp,
+            window,
+            nru,
+        }
+    }
+}
+
+impl From<NruConsumption> for pallet_smart_contract::types::NruConsumption {
+    fn from(c: NruConsumption) -> Self {
+        let NruConsumption {
+            contract_id,
+            timestamp,
+            window,
+            nru,
+        } = c;
+        Self {
+            contract_id,
+            timestamp,
+            window,
+            nru,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContractBill {
     pub contract_id: u64,
     pub timestamp: u64,
@@ -1001,6 +1476,7 @@ impl From<pallet_smart_contract_legacy::types::ContractBill> for ContractBill {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Contract {
     pub version: u32,
     pub state: ContractState,
@@ -1048,13 +1524,16 @@ impl From<pallet_smart_contract_legacy::types::Contract> for Contract {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeContract {
     pub node_id: u32,
     // deployment_data is the encrypted deployment body. This encrypted the deployment with the **USER** public key.
     // So only the user can read this data later on (or any other key that he keeps safe).
     // this data part is read only by the user and can actually hold any information to help him reconstruct his deployment or can be left empty.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_hex"))]
     pub deployment_data: Vec<u8>,
     // Hash of the deployment, set by the user
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub deployment_hash: Vec<u8>,
     pub public_ips: u32,
     pub public_ips_list: Vec<PublicIP>,
@@ -1099,6 +1578,7 @@ impl From<pallet_smart_contract_legacy::types::NodeContract> for NodeContract {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NameContract {
     pub name: String,
 }
@@ -1122,6 +1602,7 @@ impl From<pallet_smart_contract_legacy::types::NameContract> for NameContract {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RentContract {
     pub node_id: u32,
 }
@@ -1141,6 +1622,7 @@ impl From<pallet_smart_contract_legacy::types::RentContract> for RentContract {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ContractData {
     NodeContract(NodeContract),
     NameContract(NameContract),
@@ -1186,6 +1668,7 @@ impl From<pallet_smart_contract_legacy::types::ContractData> for ContractData {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ContractState {
     Created,
     Deleted(Cause),
@@ -1218,6 +1701,7 @@ impl From<pallet_smart_contract_legacy::types::ContractState> for ContractState
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cause {
     CanceledByUser,
     OutOfFunds,
@@ -1242,10 +1726,13 @@ impl From<pallet_smart_contract_legacy::types::Cause> for Cause {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CertificationCodes {
     pub version: u32,
     pub id: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub name: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub description: Vec<u8>,
     pub certification_code_type: CertificationCodeType,
 }
@@ -1289,6 +1776,7 @@ impl From<pallet_tfgrid_legacy::types::CertificationCodes> for CertificationCode
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CertificationCodeType {
     Farm,
     Entity,
@@ -1324,8 +1812,9 @@ impl From<pallet_tfgrid_legacy::types::CertificationCodeType> for CertificationC
 /// Stellar -> TF Chain minting transaction.
 /// if the votes field is larger then (number of validators / 2) + 1 , the transaction will be minted
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MintTransaction {
-    pub amount: Balance,
+    pub amount: Tft,
     pub target: AccountId32,
     pub block: BlockNumber,
     pub votes: u32,
@@ -1352,9 +1841,11 @@ impl From<pallet_tft_bridge::MintTransaction<AccountId32, u32>> for MintTransact
 /// TF Chain -> Stellar burn transaction
 /// Transaction is ready when (number of validators / 2) + 1 signatures are present
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BurnTransaction {
     pub block: BlockNumber,
-    pub amount: Balance,
+    pub amount: Tft,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub target: Vec<u8>,
     pub signatures: Vec<StellarSignature>,
     pub sequence_number: u64,
@@ -1380,10 +1871,13 @@ impl From<pallet_tft_bridge::BurnTransaction<u32>> for BurnTransaction {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RefundTransaction {
     pub block: BlockNumber,
-    pub amount: Balance,
+    pub amount: Tft,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub target: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub tx_hash: Vec<u8>,
     pub signatures: Vec<StellarSignature>,
     pub sequence_number: u64,
@@ -1411,8 +1905,11 @@ impl From<pallet_tft_bridge::RefundTransaction<u32>> for RefundTransaction {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StellarSignature {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub signature: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bytes_str"))]
     pub stellar_pub_key: Vec<u8>,
 }
 
@@ -1622,3 +2119,99 @@ impl Display for Farm {
         writeln!(f, "version: {}", self.version)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tft(s: &str) -> Tft {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn tft_display() {
+        assert_eq!(Tft::ZERO.to_string(), "0");
+        assert_eq!(Tft::from_units(10_000_000).to_string(), "1");
+        assert_eq!(Tft::from_units(125_000_000).to_string(), "12.5");
+        assert_eq!(Tft::from_units(1).to_string(), "0.0000001");
+        assert_eq!(Tft::from_units(10_000_001).to_string(), "1.0000001");
+    }
+
+    #[test]
+    fn tft_from_str() {
+        assert_eq!(tft("1"), Tft::from_units(10_000_000));
+        assert_eq!(tft("12.5"), Tft::from_units(125_000_000));
+        assert_eq!(tft("0.0000001"), Tft::from_units(1));
+        assert_eq!(tft(".5"), Tft::from_units(5_000_000));
+        assert_eq!(tft("5."), Tft::from_units(50_000_000));
+        assert_eq!(tft("007.100"), Tft::from_units(71_000_000));
+    }
+
+    #[test]
+    fn tft_round_trip() {
+        for units in [
+            0,
+            1,
+            9,
+            10,
+            1_234_567,
+            10_000_000,
+            123_456_789_012,
+            u128::MAX,
+        ] {
+            let amount = Tft::from_units(units);
+            assert_eq!(tft(&amount.to_string()), amount, "{}", units);
+        }
+        for s in ["0", "1", "0.1", "12.5", "1.0000001", "1000000"] {
+            assert_eq!(tft(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn tft_from_str_rejects_invalid_amounts() {
+        for s in [
+            "",
+            ".",
+            "1.2.3",
+            "-1",
+            "+1",
+            "1,5",
+            " 1",
+            "1e7",
+            "0.00000001",
+            "340282366920938463463374607431768211456",
+        ] {
+            assert!(
+                matches!(s.parse::<Tft>(), Err(Error::InvalidInput(_))),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn tft_from_str_rejects_overflow_after_scaling() {
+        // Fits in a u128 as whole TFT, but not as units.
+        let s = (u128::MAX / Tft::UNITS_PER_TFT + 1).to_string();
+        assert!(s.parse::<Tft>().is_err());
+    }
+
+    #[test]
+    fn tft_division_rounds_down() {
+        assert_eq!(tft("1").checked_div(3), Some(Tft::from_units(3_333_333)));
+        assert_eq!(Tft::from_units(5).checked_div(2), Some(Tft::from_units(2)));
+        assert_eq!(Tft::from_units(5).checked_div(0), None);
+    }
+
+    #[test]
+    fn tft_checked_arithmetic() {
+        assert_eq!(Tft::from_tft(2), Some(tft("2")));
+        assert_eq!(Tft::from_tft(u128::MAX), None);
+        assert_eq!(tft("1").checked_sub(tft("2")), None);
+        assert_eq!(tft("1").saturating_sub(tft("2")), Tft::ZERO);
+        assert_eq!(
+            Tft::from_units(u128::MAX).checked_add(Tft::from_units(1)),
+            None
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Validation of user input before it is submitted to the chain. Extrinsics with invalid input
+//! would be rejected by the runtime anyway, but only after paying fees and waiting for a block.
+
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+/// The version byte of a Stellar strkey encoded account id (ed25519 public key).
+const STELLAR_ACCOUNT_VERSION_BYTE: u8 = 6 << 3;
+
+/// The length of a Stellar strkey encoded account id.
+const STELLAR_ADDRESS_LENGTH: usize = 56;
+
+/// Check that a farm name only contains alphanumeric characters, `-` and `_`.
+pub fn farm_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(Error::InvalidInput("farm name can't be empty".into()));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::InvalidInput(format!(
+            "farm name {} can only contain alphanumeric characters, '-' and '_'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Check that `ip` is an IPv4 address in CIDR notation, e.g. `185.206.122.33/24`.
+pub fn ipv4_cidr(ip: &str) -> Result<(Ipv4Addr, u8)> {
+    let invalid = || Error::InvalidInput(format!("{} is not an IPv4 address in CIDR notation", ip));
+    let (addr, prefix) = ip.split_once('/').ok_or_else(invalid)?;
+    let addr = addr.parse::<Ipv4Addr>().map_err(|_| invalid())?;
+    let prefix = prefix.parse::<u8>().map_err(|_| invalid())?;
+    if prefix > 32 {
+        return Err(invalid());
+    }
+    Ok((addr, prefix))
+}
+
+/// Check that `gateway` is a plain IPv4 address in the subnet of `ip`, which is in CIDR
+/// notation, and that it is not the same address as `ip`.
+pub fn ipv4_gateway(ip: &str, gateway: &str) -> Result<()> {
+    let (addr, prefix) = ipv4_cidr(ip)?;
+    let gw = gateway
+        .parse::<Ipv4Addr>()
+        .map_err(|_| Error::InvalidInput(format!("{} is not a valid IPv4 gateway", gateway)))?;
+    if gw == addr {
+        return Err(Error::InvalidInput(format!(
+            "gateway {} can't be the same as the ip",
+            gateway
+        )));
+    }
+    let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+    if u32::from(gw) & mask != u32::from(addr) & mask {
+        return Err(Error::InvalidInput(format!(
+            "gateway {} is not in the subnet of {}",
+            gateway, ip
+        )));
+    }
+    Ok(())
+}
+
+/// Check that `address` is a valid Stellar account id in strkey encoding, i.e. a `G...` address
+/// with a correct checksum.
+pub fn stellar_address(address: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Error::InvalidInput(format!(
+            "{} is not a valid stellar address: {}",
+            address, reason
+        ))
+    };
+    if address.len() != STELLAR_ADDRESS_LENGTH {
+        return Err(invalid("wrong length"));
+    }
+    let raw = base32_decode(address).ok_or_else(|| invalid("invalid base32 encoding"))?;
+    // version byte + 32 byte public key + 2 byte checksum
+    if raw.len() != 35 {
+        return Err(invalid("wrong length"));
+    }
+    if raw[0] != STELLAR_ACCOUNT_VERSION_BYTE {
+        return Err(invalid("not an account id"));
+    }
+    let (payload, checksum) = raw.split_at(33);
+    if crc16_xmodem(payload).to_le_bytes() != checksum {
+        return Err(invalid("checksum mismatch"));
+    }
+    Ok(())
+}
+
+/// Check that the timestamps of reports are strictly increasing for every contract, in the order
+/// in which the reports are given. `key` returns the contract id and timestamp of a report.
+pub fn monotonic_timestamps<T, F>(reports: &[T], key: F) -> Result<()>
+where
+    F: Fn(&T) -> (u64, u64),
+{
+    let mut last = HashMap::new();
+    for (contract_id, timestamp) in reports.iter().map(key) {
+        if let Some(previous) = last.insert(contract_id, timestamp) {
+            if timestamp <= previous {
+                return Err(Error::InvalidInput(format!(
+                    "report for contract {} at {} is not after the previous report at {}",
+                    contract_id, timestamp, previous
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decode an unpadded RFC 4648 base32 string.
+fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in input.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// CRC-16/XMODEM, as used for Stellar strkey checksums.
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A valid Stellar account id.
+    const STELLAR_ADDRESS: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+    #[test]
+    fn valid_farm_names() {
+        for name in ["farm", "my-farm_01", "F"] {
+            assert!(farm_name(name).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn invalid_farm_names() {
+        for name in ["", "my farm", "farm!", "färm", "farm.1"] {
+            assert!(
+                matches!(farm_name(name), Err(Error::InvalidInput(_))),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn valid_ipv4_cidr() {
+        assert_eq!(
+            ipv4_cidr("185.206.122.33/24").unwrap(),
+            (Ipv4Addr::new(185, 206, 122, 33), 24)
+        );
+        assert_eq!(
+            ipv4_cidr("10.0.0.1/0").unwrap(),
+            (Ipv4Addr::new(10, 0, 0, 1), 0)
+        );
+        assert_eq!(
+            ipv4_cidr("10.0.0.1/32").unwrap(),
+            (Ipv4Addr::new(10, 0, 0, 1), 32)
+        );
+    }
+
+    #[test]
+    fn invalid_ipv4_cidr() {
+        for ip in [
+            "185.206.122.33",
+            "185.206.122.33/33",
+            "185.206.122/24",
+            "185.206.122.256/24",
+            "::1/64",
+            "185.206.122.33/",
+            "/24",
+        ] {
+            assert!(ipv4_cidr(ip).is_err(), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn gateway_in_subnet() {
+        assert!(ipv4_gateway("185.206.122.33/24", "185.206.122.1").is_ok());
+        assert!(ipv4_gateway("10.1.2.3/8", "10.200.0.1").is_ok());
+        // Every address is in the subnet of a /0 prefix.
+        assert!(ipv4_gateway("10.1.2.3/0", "192.168.0.1").is_ok());
+    }
+
+    #[test]
+    fn gateway_outside_subnet() {
+        assert!(ipv4_gateway("185.206.122.33/24", "185.206.123.1").is_err());
+        assert!(ipv4_gateway("10.0.0.2/32", "10.0.0.1").is_err());
+    }
+
+    #[test]
+    fn gateway_is_not_the_ip() {
+        assert!(ipv4_gateway("185.206.122.33/24", "185.206.122.33").is_err());
+    }
+
+    #[test]
+    fn invalid_gateway() {
+        assert!(ipv4_gateway("185.206.122.33/24", "185.206.122.1/24").is_err());
+        assert!(ipv4_gateway("185.206.122.33", "185.206.122.1").is_err());
+    }
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16_xmodem(b"123456789"), 0x31c3);
+        assert_eq!(crc16_xmodem(b""), 0);
+    }
+
+    #[test]
+    fn valid_stellar_address() {
+        assert!(stellar_address(STELLAR_ADDRESS).is_ok());
+    }
+
+    #[test]
+    fn stellar_address_checksum_mismatch() {
+        // Change a single character of the public key.
+        let address = STELLAR_ADDRESS.replacen("GBRP", "GBRQ", 1);
+        assert!(stellar_address(&address).is_err());
+    }
+
+    #[test]
+    fn invalid_stellar_addresses() {
+        let lowercase = STELLAR_ADDRESS.to_lowercase();
+        let too_short = &STELLAR_ADDRESS[1..];
+        // A valid seed, which is not an account id.
+        let seed = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+        for address in [lowercase.as_str(), too_short, seed, ""] {
+            assert!(stellar_address(address).is_err(), "{}", address);
+        }
+    }
+
+    #[test]
+    fn increasing_timestamps() {
+        let reports = [(1, 10), (2, 5), (1, 11), (2, 20)];
+        assert!(monotonic_timestamps(&reports, |r| *r).is_ok());
+    }
+
+    #[test]
+    fn repeated_timestamp() {
+        let reports = [(1, 10), (1, 10)];
+        assert!(monotonic_timestamps(&reports, |r| *r).is_err());
+    }
+
+    #[test]
+    fn decreasing_timestamp() {
+        let reports = [(1, 10), (2, 5), (2, 4)];
+        assert!(monotonic_timestamps(&reports, |r| *r).is_err());
+    }
+}
@@ -1,13 +1,17 @@
 //! Abstractions for working with chain storage at fixed times (i.e. blocks)
 
-use crate::client::SharedClient;
+use crate::calls;
+use crate::client::{FinalizedBlocks, SharedClient, StorageKey, STORAGE_PAGE_SIZE};
+use crate::error::Error;
 use crate::events;
+use crate::filter::EventFilter;
+use crate::retry::ErrorKind;
 use crate::types::{BlockNumber, Contract, ContractState, Farm, Hash, Node, Resources};
 use chrono::prelude::*;
 use sp_core::crypto::Pair;
 use std::fmt;
+use std::thread;
 use substrate_api_client::sp_runtime::MultiSignature;
-use substrate_api_client::ApiClientError;
 
 /// The [Result](std::result::Result) type used by [Window] operations.
 pub type WindowResult<T> = Result<T, WindowError>;
@@ -22,7 +26,6 @@ where
 {
     client: EventTypedClient<P>,
     target: Option<(BlockNumber, Hash)>,
-    network: Network,
 }
 
 impl<P> Window<P>
@@ -32,44 +35,27 @@ where
 {
     /// Create a new [Window] at the given height. If the used block height does not exist yet on
     /// the chain, Ok(None) is returned.
-    pub fn at_height<C>(
-        client: C,
-        height: BlockNumber,
-        network: Network,
-    ) -> WindowResult<Option<Window<P>>>
+    ///
+    /// The events of the block are decoded with the runtime types matching the spec version of
+    /// the runtime at that block, see [Decoder].
+    pub fn at_height<C>(client: C, height: BlockNumber) -> WindowResult<Option<Window<P>>>
     where
         C: Into<EventTypedClient<P>>,
     {
-        let client = match network {
-            Network::Main => {
-                // TODO: force legacy as update is not live yet
-                if height < 3_899_093 {
-                    client.into().as_legacy()
-                } else {
-                    client.into().as_current()
-                }
-            }
-            Network::Test => {
-                if height < 3_615_198 {
-                    client.into().as_legacy()
-                } else {
-                    client.into().as_current()
-                }
-            }
-            Network::Dev => {
-                // TODO
-                if height < 1_500_000 {
-                    client.into().as_legacy()
-                } else {
-                    client.into().as_current()
-                }
-            }
+        let client = client.into();
+        let hash = match client.get_hash_at_height(height)? {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        let spec_version = client.spec_version_at(height, hash)?;
+        let client = match Decoder::for_spec_version(spec_version) {
+            Decoder::Legacy => client.as_legacy(),
+            Decoder::Current => client.as_current(),
         };
 
-        Ok(client.get_hash_at_height(height)?.map(|hash| Window {
+        Ok(Some(Window {
             client,
             target: Some((height, hash)),
-            network,
         }))
     }
 
@@ -84,7 +70,7 @@ where
     pub fn advance(&self) -> WindowResult<Option<Window<P>>> {
         let client = self.client.clone();
         if let Some((h, _)) = self.target {
-            Self::at_height(client, h + 1, self.network)
+            Self::at_height(client, h + 1)
         } else {
             Err(WindowError::NonHistoricWindow)
         }
@@ -95,7 +81,7 @@ where
     pub fn advance_by(&self, amount: BlockNumber) -> WindowResult<Option<Window<P>>> {
         let client = self.client.clone();
         if let Some((h, _)) = self.target {
-            Self::at_height(client, h + amount, self.network)
+            Self::at_height(client, h + amount)
         } else {
             Err(WindowError::NonHistoricWindow)
         }
@@ -106,7 +92,7 @@ where
     pub fn previous(&self) -> WindowResult<Option<Window<P>>> {
         let client = self.client.clone();
         if let Some((h, _)) = self.target {
-            Self::at_height(client, h - 1, self.network)
+            Self::at_height(client, h - 1)
         } else {
             Err(WindowError::NonHistoricWindow)
         }
@@ -117,17 +103,31 @@ where
     pub fn previous_by(&self, amount: BlockNumber) -> WindowResult<Option<Window<P>>> {
         let client = self.client.clone();
         if let Some((h, _)) = self.target {
-            Self::at_height(client, h - amount, self.network)
+            Self::at_height(client, h - amount)
         } else {
             Err(WindowError::NonHistoricWindow)
         }
     }
 
-    /// Get the [events](events::TfchainEvent) for the block pointed at by the window.
-    pub fn events(&self) -> WindowResult<Vec<events::TfchainEvent>> {
+    /// Get the [events](events::BlockEvent) for the block pointed at by the window.
+    pub fn events(&self) -> WindowResult<Vec<events::BlockEvent>> {
         Ok(self.client.get_block_events(self.hash())?)
     }
 
+    /// Get the [events](events::BlockEvent) for the block pointed at by the window which match a
+    /// [filter](EventFilter).
+    pub fn filtered_events(&self, filter: &EventFilter) -> WindowResult<Vec<events::BlockEvent>> {
+        let mut events = self.events()?;
+        events.retain(|e| filter.matches(&e.event));
+        Ok(events)
+    }
+
+    /// Get the [extrinsics](calls::Extrinsic) in the block pointed at by the window, together with
+    /// the events they emitted.
+    pub fn extrinsics(&self) -> WindowResult<Vec<calls::Extrinsic>> {
+        Ok(self.client.get_block_extrinsics(self.hash())?)
+    }
+
     /// Gets the date at which the block pointed to by this [Window] was made.
     pub fn date(&self) -> WindowResult<DateTime<Utc>> {
         let ts = self.client.block_timestamp(self.hash())?;
@@ -139,31 +139,32 @@ where
         if let Some((height, _)) = self.target {
             Ok(height)
         } else {
-            todo!();
+            Err(WindowError::NonHistoricWindow)
         }
     }
 
+    /// Get the hash of the block pointed to by this [Window].
+    pub fn block_hash(&self) -> WindowResult<Hash> {
+        self.hash().ok_or(WindowError::NonHistoricWindow)
+    }
+
     /// Get an iterator returning all farms in the current [Window]. If the [Window] is not
     /// historic, slow consumption can lead to innacurate results.
     pub fn farms(&self) -> WindowResult<FarmIterator<P>> {
-        let amount = self.client.farm_count(self.hash())?;
         Ok(FarmIterator {
             client: self.client.clone(),
             block: self.hash(),
-            amount,
-            current: 0,
+            pages: Pages::new(),
         })
     }
 
     /// Get an iterator returning all nodes in the current [Window]. If the [Window] is not
     /// historic, slow consumption can lead to innacurate results.
     pub fn nodes(&self) -> WindowResult<NodeIterator<P>> {
-        let amount = self.client.node_count(self.hash())?;
         Ok(NodeIterator {
             client: self.client.clone(),
             block: self.hash(),
-            amount,
-            current: 0,
+            pages: Pages::new(),
         })
     }
 
@@ -171,12 +172,10 @@ where
     /// historic, slow consumption can lead to inaccurate results. If deployed is true, only
     /// contracts currently deployed will be returned.
     pub fn contracts(&self, live: bool) -> WindowResult<ContractIterator<P>> {
-        let amount = self.client.contract_count(self.hash())?;
         Ok(ContractIterator {
             client: self.client.clone(),
             block: self.hash(),
-            amount,
-            current: 0,
+            pages: Pages::new(),
             live,
         })
     }
@@ -195,8 +194,8 @@ where
     }
 }
 
-// TODO: these 3 iterators could technically be made generic, by taking a Fn with output type as
-// generic to the output of the iterator
+/// An iterator over all nodes in a [Window], ordered by id. Nodes are fetched in pages of
+/// [STORAGE_PAGE_SIZE], after the keys of all nodes are listed.
 pub struct NodeIterator<P>
 where
     P: Pair,
@@ -204,8 +203,7 @@ where
 {
     client: EventTypedClient<P>,
     block: Option<Hash>,
-    amount: u32,
-    current: u32,
+    pages: Pages<Node>,
 }
 
 impl<P> Iterator for NodeIterator<P>
@@ -216,28 +214,16 @@ where
     type Item = WindowResult<Node>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            // Nodes start at index 1
-            self.current += 1;
-            if self.current > self.amount {
-                return None;
-            }
-
-            return match self
-                .client
-                .get_node_by_id(self.current, self.block)
-                .map_err(WindowError::from)
-            {
-                Ok(maybe_node) => match maybe_node {
-                    Some(node) => Some(Ok(node)),
-                    None => continue,
-                },
-                Err(err) => Some(Err(err)),
-            };
-        }
+        let (client, block) = (&self.client, self.block);
+        self.pages.next(
+            || client.get_node_keys(block),
+            |keys| client.get_nodes_at(keys, block),
+        )
     }
 }
 
+/// An iterator over all farms in a [Window], ordered by id. Farms are fetched in pages of
+/// [STORAGE_PAGE_SIZE], after the keys of all farms are listed.
 pub struct FarmIterator<P>
 where
     P: Pair,
@@ -245,8 +231,7 @@ where
 {
     client: EventTypedClient<P>,
     block: Option<Hash>,
-    amount: u32,
-    current: u32,
+    pages: Pages<Farm>,
 }
 
 impl<P> Iterator for FarmIterator<P>
@@ -257,28 +242,17 @@ where
     type Item = WindowResult<Farm>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            // Farms start at index 1
-            self.current += 1;
-            if self.current > self.amount {
-                return None;
-            }
-
-            return match self
-                .client
-                .get_farm_by_id(self.current, self.block)
-                .map_err(WindowError::from)
-            {
-                Ok(maybe_farm) => match maybe_farm {
-                    Some(farm) => Some(Ok(farm)),
-                    None => continue,
-                },
-                Err(err) => Some(Err(err)),
-            };
-        }
+        let (client, block) = (&self.client, self.block);
+        self.pages.next(
+            || client.get_farm_keys(block),
+            |keys| client.get_farms_at(keys, block),
+        )
     }
 }
 
+/// An iterator over all contracts in a [Window], together with their used resources, ordered by
+/// id. Contracts are fetched in pages of [STORAGE_PAGE_SIZE], after the keys of all contracts are
+/// listed.
 pub struct ContractIterator<P>
 where
     P: Pair,
@@ -286,8 +260,7 @@ where
 {
     client: EventTypedClient<P>,
     block: Option<Hash>,
-    amount: u64,
-    current: u64,
+    pages: Pages<(Contract, Resources)>,
     live: bool,
 }
 
@@ -299,60 +272,236 @@ where
     type Item = WindowResult<(Contract, Resources)>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let (client, block) = (&self.client, self.block);
         loop {
-            // Contracts start at index 1
-            self.current += 1;
-            if self.current > self.amount {
+            match self.pages.next(
+                || client.get_contract_keys(block),
+                |keys| client.get_contracts_at(keys, block),
+            )? {
+                Ok((contract, _))
+                    if self.live && !matches!(contract.state, ContractState::Created) =>
+                {
+                    continue
+                }
+                res => return Some(res),
+            }
+        }
+    }
+}
+
+/// The values of a storage map which are paged through by an iterator.
+struct Pages<T> {
+    /// The remaining values of the current page.
+    page: std::vec::IntoIter<T>,
+    /// The keys of all values in the map, ordered by id. `None` before they are listed.
+    keys: Option<Vec<StorageKey>>,
+    /// The amount of keys of which the values have been fetched.
+    fetched: usize,
+}
+
+impl<T> Pages<T> {
+    fn new() -> Self {
+        Pages {
+            page: Vec::new().into_iter(),
+            keys: None,
+            fetched: 0,
+        }
+    }
+
+    /// Get the next value. The keys of all values are listed with `list` first, after which the
+    /// values are fetched with `fetch`, a page at a time, once the current page is exhausted. If
+    /// listing or fetching fails, the error is returned and the call is tried again on the next
+    /// call.
+    fn next<L, F>(&mut self, list: L, fetch: F) -> Option<WindowResult<T>>
+    where
+        L: Fn() -> crate::client::ApiResult<Vec<StorageKey>>,
+        F: Fn(&[StorageKey]) -> crate::client::ApiResult<Vec<T>>,
+    {
+        loop {
+            if let Some(value) = self.page.next() {
+                return Some(Ok(value));
+            }
+            if self.keys.is_none() {
+                match list() {
+                    Ok(keys) => self.keys = Some(keys),
+                    Err(err) => return Some(Err(err.into())),
+                }
+            }
+            let keys = self.keys.as_deref().unwrap_or_default();
+            if self.fetched == keys.len() {
                 return None;
             }
+            let end = keys.len().min(self.fetched + STORAGE_PAGE_SIZE as usize);
+            match fetch(&keys[self.fetched..end]) {
+                Ok(values) => {
+                    self.fetched = end;
+                    self.page = values.into_iter();
+                }
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+/// A finalized block with its events, as returned by a [BlockStream] or a
+/// [RangeScanner](crate::scanner::RangeScanner).
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FinalizedBlock {
+    pub height: BlockNumber,
+    pub hash: Hash,
+    /// The time at which the block was made, in milliseconds since the unix epoch.
+    pub timestamp: i64,
+    /// The events of the block, decoded with the runtime which produced it.
+    pub events: Vec<events::BlockEvent>,
+}
+
+impl FinalizedBlock {
+    /// Gets the date at which the block was made.
+    pub fn date(&self) -> DateTime<Utc> {
+        Utc.timestamp(
+            self.timestamp / 1000,
+            (self.timestamp % 1000) as u32 * 1_000_000,
+        )
+    }
+}
+
+/// An iterator over every finalized block, with its timestamp and events. The events of every
+/// block are decoded with the runtime types matching the spec version of the runtime at that
+/// block, see [Decoder].
+///
+/// A stream started at a historic height first catches up with the finalized head, after which
+/// it follows new blocks as they are finalized. Blocks are never skipped, see [FinalizedBlocks].
+/// If loading a block fails with a [transient](WindowError::is_transient) error, the error is
+/// returned and the same block is tried again when the iterator is polled next, after a delay
+/// which grows with every failed attempt according to the [RetryPolicy](crate::RetryPolicy) of
+/// the client. After any other error the stream ends.
+pub struct BlockStream<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    loader: BlockLoader<P>,
+    heads: FinalizedBlocks<P, runtime::Event>,
+    /// A block which could not be loaded yet.
+    pending: Option<(BlockNumber, Hash)>,
+    /// The amount of failed attempts to load the pending block.
+    failures: u32,
+    /// Set after an error which is not transient, to end the stream.
+    stopped: bool,
+    filter: Option<EventFilter>,
+}
 
-            return match self
-                .client
-                .get_contract_by_id(self.current, self.block)
-                .map_err(WindowError::from)
-            {
-                Ok(maybe_contract) => match maybe_contract {
-                    Some((contract, cr)) => {
-                        if self.live {
-                            if matches!(contract.state, ContractState::Created) {
-                                Some(Ok((contract, cr)))
-                            } else {
-                                continue;
-                            }
-                        } else {
-                            Some(Ok((contract, cr)))
-                        }
-                    }
-                    None => continue,
-                },
-                Err(err) => Some(Err(err)),
-            };
+impl<P> BlockStream<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    /// Create a new [BlockStream]. If `from` is set, the stream starts at the block with that
+    /// height, otherwise it starts at the next block which is finalized.
+    pub fn new<C>(client: C, from: Option<BlockNumber>) -> BlockStream<P>
+    where
+        C: Into<EventTypedClient<P>>,
+    {
+        let loader = BlockLoader::new(client.into());
+        // Following the finalized heads does not depend on the runtime.
+        let heads = loader.current.finalized_blocks(from);
+        BlockStream {
+            loader,
+            heads,
+            pending: None,
+            failures: 0,
+            stopped: false,
+            filter: None,
         }
     }
+
+    /// Only keep the events matching the [filter](EventFilter) in the returned blocks. Blocks
+    /// without matching events are still returned, so callers can keep track of their progress.
+    pub fn with_filter(mut self, filter: EventFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+}
+
+impl<P> Iterator for BlockStream<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    type Item = WindowResult<FinalizedBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stopped {
+            return None;
+        }
+        let (height, hash) = match self.pending.take() {
+            Some(block) => {
+                let policy = self.loader.current.retry_policy();
+                thread::sleep(policy.backoff(self.failures));
+                block
+            }
+            None => match self.heads.next()? {
+                Ok(block) => block,
+                Err(err) => {
+                    let err = WindowError::from(err);
+                    self.stopped = !err.is_transient();
+                    return Some(Err(err));
+                }
+            },
+        };
+        let res = self.loader.load_block(height, hash, self.filter.as_ref());
+        match res {
+            Ok(_) => self.failures = 0,
+            Err(ref err) if err.is_transient() => {
+                self.failures += 1;
+                self.pending = Some((height, hash));
+            }
+            Err(_) => self.stopped = true,
+        }
+        Some(res)
+    }
 }
 
 /// A `WindowError` contains details about errors when working with [Window]s
 #[derive(Debug)]
 pub enum WindowError {
     /// An error while executing a call to the chain
-    Api(ApiClientError),
+    Api(Error),
     /// Result of trying to advance or go back from a [Window] pointing to the head of the current
     /// chain.
     NonHistoricWindow,
+    /// A worker of a [RangeScanner](crate::scanner::RangeScanner) panicked while loading the
+    /// block at this height.
+    WorkerPanicked(BlockNumber),
+}
+
+impl WindowError {
+    /// Indicates if the error is caused by the connection to the chain or by the node, so the
+    /// same call could succeed if it is tried again.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WindowError::Api(e) => matches!(
+                ErrorKind::of(e),
+                ErrorKind::Disconnected | ErrorKind::Connection | ErrorKind::Rpc
+            ),
+            WindowError::NonHistoricWindow | WindowError::WorkerPanicked(_) => false,
+        }
+    }
 }
 
 impl fmt::Display for WindowError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                WindowError::Api(ref apie) => apie as &dyn fmt::Display,
-                WindowError::NonHistoricWindow =>
-                    &"method call expected historic window, found window pointing to head"
-                        as &dyn fmt::Display,
+        match self {
+            WindowError::Api(ref apie) => write!(f, "{}", apie),
+            WindowError::NonHistoricWindow => write!(
+                f,
+                "method call expected historic window, found window pointing to head"
+            ),
+            WindowError::WorkerPanicked(height) => {
+                write!(f, "worker panicked while loading block {}", height)
             }
-        )
+        }
     }
 }
 
@@ -365,18 +514,40 @@ impl std::error::Error for WindowError {
     }
 }
 
-impl From<ApiClientError> for WindowError {
-    fn from(ace: ApiClientError) -> Self {
-        WindowError::Api(ace)
+impl From<Error> for WindowError {
+    fn from(e: Error) -> Self {
+        WindowError::Api(e)
     }
 }
 
-/// Grid networks, mainly used to identify
-#[derive(Debug, Clone, Copy)]
-pub enum Network {
-    Main,
-    Test,
-    Dev,
+/// The runtime types used to decode the events of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decoder {
+    /// Types of the legacy runtime, see [runtime_legacy].
+    Legacy,
+    /// Types of the latest runtime known to this library, see [runtime].
+    Current,
+}
+
+/// Mapping of runtime spec versions to the [Decoder] for blocks produced by that runtime. Every
+/// entry applies from its spec version up to, but not including, the spec version of the next
+/// entry. Entries must be sorted by spec version.
+const DECODERS: &[(u32, Decoder)] = &[
+    (0, Decoder::Legacy),
+    (runtime_legacy::VERSION.spec_version + 1, Decoder::Current),
+];
+
+impl Decoder {
+    /// Get the [Decoder] to use for blocks produced by the runtime with the given spec version.
+    pub fn for_spec_version(spec_version: u32) -> Decoder {
+        DECODERS
+            .iter()
+            .rev()
+            .find(|(from, _)| *from <= spec_version)
+            .map(|(_, decoder)| *decoder)
+            // The first entry starts at 0, so there is always a match.
+            .unwrap_or(Decoder::Legacy)
+    }
 }
 
 /// The client with actual event types.
@@ -425,57 +596,64 @@ where
         }
     }
 
-    fn get_farm_by_id(
-        &self,
-        farm_id: u32,
-        block: Option<Hash>,
-    ) -> crate::client::ApiResult<Option<Farm>> {
+    fn get_farm_keys(&self, block: Option<Hash>) -> crate::client::ApiResult<Vec<StorageKey>> {
         match self {
-            EventTypedClient::Current(ref sc) => sc.get_farm_by_id(farm_id, block),
-            EventTypedClient::Legacy(ref sc) => sc.get_farm_by_id(farm_id, block),
+            EventTypedClient::Current(ref sc) => sc.get_farm_keys(block),
+            EventTypedClient::Legacy(ref sc) => sc.get_farm_keys(block),
         }
     }
 
-    fn get_node_by_id(
+    fn get_farms_at(
         &self,
-        node_id: u32,
+        keys: &[StorageKey],
         block: Option<Hash>,
-    ) -> crate::client::ApiResult<Option<Node>> {
+    ) -> crate::client::ApiResult<Vec<Farm>> {
+        match self {
+            EventTypedClient::Current(ref sc) => sc.get_farms_at(keys, block),
+            EventTypedClient::Legacy(ref sc) => sc.get_farms_at(keys, block),
+        }
+    }
+
+    fn get_node_keys(&self, block: Option<Hash>) -> crate::client::ApiResult<Vec<StorageKey>> {
         match self {
-            EventTypedClient::Current(ref sc) => sc.get_node_by_id(node_id, block),
-            EventTypedClient::Legacy(ref sc) => sc.get_node_by_id(node_id, block),
+            EventTypedClient::Current(ref sc) => sc.get_node_keys(block),
+            EventTypedClient::Legacy(ref sc) => sc.get_node_keys(block),
         }
     }
 
-    fn get_contract_by_id(
+    fn get_nodes_at(
         &self,
-        contract_id: u64,
+        keys: &[StorageKey],
         block: Option<Hash>,
-    ) -> crate::client::ApiResult<Option<(Contract, Resources)>> {
+    ) -> crate::client::ApiResult<Vec<Node>> {
         match self {
-            EventTypedClient::Current(ref sc) => {
-                match sc.get_contract_by_id(contract_id, block)? {
-                    None => Ok(None),
-                    Some(c) => Ok(Some((
-                        c,
-                        sc.get_contract_resources(contract_id, block)?
-                            .unwrap_or_default()
-                            .used,
-                    ))),
-                }
-            }
-            EventTypedClient::Legacy(ref sc) => match sc.get_contract_by_id(contract_id, block)? {
-                None => Ok(None),
-                Some(c) => Ok(Some((
-                    c,
-                    sc.get_contract_resources(contract_id, block)?
-                        .unwrap_or_default()
-                        .used,
-                ))),
-            },
+            EventTypedClient::Current(ref sc) => sc.get_nodes_at(keys, block),
+            EventTypedClient::Legacy(ref sc) => sc.get_nodes_at(keys, block),
+        }
+    }
+
+    fn get_contract_keys(&self, block: Option<Hash>) -> crate::client::ApiResult<Vec<StorageKey>> {
+        match self {
+            EventTypedClient::Current(ref sc) => sc.get_contract_keys(block),
+            EventTypedClient::Legacy(ref sc) => sc.get_contract_keys(block),
         }
     }
 
+    fn get_contracts_at(
+        &self,
+        keys: &[StorageKey],
+        block: Option<Hash>,
+    ) -> crate::client::ApiResult<Vec<(Contract, Resources)>> {
+        let contracts = match self {
+            EventTypedClient::Current(ref sc) => sc.get_contracts_at(keys, block)?,
+            EventTypedClient::Legacy(ref sc) => sc.get_contracts_at(keys, block)?,
+        };
+        Ok(contracts
+            .into_iter()
+            .map(|(c, cr)| (c, cr.unwrap_or_default().used))
+            .collect())
+    }
+
     fn get_farm_payout_address(
         &self,
         farm_id: u32,
@@ -487,42 +665,106 @@ where
         }
     }
 
-    fn contract_count(&self, block: Option<Hash>) -> crate::client::ApiResult<u64> {
+    fn get_block_events(
+        &self,
+        block: Option<Hash>,
+    ) -> crate::client::ApiResult<Vec<events::BlockEvent>> {
         match self {
-            EventTypedClient::Current(ref sc) => sc.contract_count(block),
-            EventTypedClient::Legacy(ref sc) => sc.contract_count(block),
+            EventTypedClient::Current(ref sc) => sc.get_block_events(block),
+            EventTypedClient::Legacy(ref sc) => sc.get_block_events(block),
         }
     }
 
-    fn node_count(&self, block: Option<Hash>) -> crate::client::ApiResult<u32> {
+    fn get_block_extrinsics(
+        &self,
+        block: Option<Hash>,
+    ) -> crate::client::ApiResult<Vec<calls::Extrinsic>> {
         match self {
-            EventTypedClient::Current(ref sc) => sc.node_count(block),
-            EventTypedClient::Legacy(ref sc) => sc.node_count(block),
+            EventTypedClient::Current(ref sc) => sc.get_block_extrinsics(block),
+            EventTypedClient::Legacy(ref sc) => sc.get_block_extrinsics(block),
         }
     }
 
-    fn farm_count(&self, block: Option<Hash>) -> crate::client::ApiResult<u32> {
+    pub(crate) fn get_hash_at_height(&self, height: u32) -> crate::client::ApiResult<Option<Hash>> {
         match self {
-            EventTypedClient::Current(ref sc) => sc.farm_count(block),
-            EventTypedClient::Legacy(ref sc) => sc.farm_count(block),
+            EventTypedClient::Current(ref sc) => sc.get_hash_at_height(height),
+            EventTypedClient::Legacy(ref sc) => sc.get_hash_at_height(height),
         }
     }
 
-    fn get_block_events(
-        &self,
-        block: Option<Hash>,
-    ) -> crate::client::ApiResult<Vec<events::TfchainEvent>> {
+    pub(crate) fn height_at_timestamp(&self, ts: i64) -> crate::client::ApiResult<BlockNumber> {
         match self {
-            EventTypedClient::Current(ref sc) => sc.get_block_events(block),
-            EventTypedClient::Legacy(ref sc) => sc.get_block_events(block),
+            EventTypedClient::Current(ref sc) => sc.height_at_timestamp(ts),
+            EventTypedClient::Legacy(ref sc) => sc.height_at_timestamp(ts),
         }
     }
 
-    fn get_hash_at_height(&self, height: u32) -> crate::client::ApiResult<Option<Hash>> {
+    fn spec_version_at(&self, height: BlockNumber, hash: Hash) -> crate::client::ApiResult<u32> {
         match self {
-            EventTypedClient::Current(ref sc) => sc.get_hash_at_height(height),
-            EventTypedClient::Legacy(ref sc) => sc.get_hash_at_height(height),
+            EventTypedClient::Current(ref sc) => sc.spec_version_at(height, hash),
+            EventTypedClient::Legacy(ref sc) => sc.spec_version_at(height, hash),
+        }
+    }
+}
+
+/// Loads blocks with a client for every runtime. The clients are converted once, when the loader
+/// is created, rather than for every block.
+#[derive(Clone)]
+pub(crate) struct BlockLoader<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    current: SharedClient<P, runtime::Event>,
+    legacy: SharedClient<P, runtime_legacy::Event>,
+}
+
+impl<P> BlockLoader<P>
+where
+    P: Pair,
+    MultiSignature: From<P::Signature>,
+{
+    pub(crate) fn new(client: EventTypedClient<P>) -> Self {
+        let (current, legacy) = match client {
+            EventTypedClient::Current(sc) => (sc.clone(), sc.with_events()),
+            EventTypedClient::Legacy(sc) => (sc.clone().with_events(), sc),
+        };
+        BlockLoader { current, legacy }
+    }
+
+    /// The client using the latest runtime, for calls which do not depend on the runtime.
+    pub(crate) fn client(&self) -> &SharedClient<P, runtime::Event> {
+        &self.current
+    }
+
+    /// Load the timestamp and events of a block, decoding the events with the runtime which
+    /// produced the block. Only the events matching the filter are kept, if one is given.
+    pub(crate) fn load_block(
+        &self,
+        height: BlockNumber,
+        hash: Hash,
+        filter: Option<&EventFilter>,
+    ) -> WindowResult<FinalizedBlock> {
+        let spec_version = self.current.spec_version_at(height, hash)?;
+        let (mut events, timestamp) = match Decoder::for_spec_version(spec_version) {
+            Decoder::Legacy => (
+                self.legacy.get_block_events(Some(hash))?,
+                self.legacy.block_timestamp(Some(hash))?,
+            ),
+            Decoder::Current => (
+                self.current.get_block_events(Some(hash))?,
+                self.current.block_timestamp(Some(hash))?,
+            ),
+        };
+        if let Some(filter) = filter {
+            events.retain(|e| filter.matches(&e.event));
         }
+        Ok(FinalizedBlock {
+            height,
+            hash,
+            timestamp,
+            events,
+        })
     }
 }
 
@@ -545,3 +787,17 @@ where
         EventTypedClient::Legacy(sc)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_api_client::ApiClientError;
+
+    #[test]
+    fn only_connection_errors_are_transient() {
+        assert!(WindowError::Api(Error::Transport(ApiClientError::Genesis)).is_transient());
+        assert!(!WindowError::Api(Error::NotFound("block at height 1".into())).is_transient());
+        assert!(!WindowError::Api(Error::RuntimeMismatch("block events".into())).is_transient());
+        assert!(!WindowError::NonHistoricWindow.is_transient());
+    }
+}